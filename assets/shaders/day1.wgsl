@@ -1,15 +1,45 @@
 @group(0) @binding(0)
 var texture: texture_storage_2d<rgba8unorm, read_write>;
+// One word per input line, 0 for the blank lines between elves.
 @group(0) @binding(1)
-var<storage,read_write> input_array: array<u32>;
+var<storage,read> input_array: array<u32>;
+// The three largest totals, largest first.
 @group(0) @binding(2)
 var<storage,read_write> output_array: array<u32>;
+// Each elf's total, at the index of its first line; 0 everywhere else.
+@group(0) @binding(3)
+var<storage,read_write> elf_totals: array<u32>;
+
+struct Dims {
+    // Input lines, the length of input_array and elf_totals.
+    lines: u32,
+    // The texture the lines are painted into, row by row.
+    width: u32,
+    height: u32,
+}
+@group(0) @binding(4)
+var<uniform> dims: Dims;
+// One bit per input line, set for the blank lines between elves; 32 lines to
+// a word. Kept apart from input_array so any calorie count, 0 included, is a
+// line of its own.
+@group(0) @binding(5)
+var<storage,read> blank_lines: array<u32>;
+
+fn is_blank(idx: u32) -> bool {
+    return ((blank_lines[idx / 32u] >> (idx % 32u)) & 1u) == 1u;
+}
 
 @compute @workgroup_size(8, 8, 1)
 fn init(@builtin(global_invocation_id) invocation_id: vec3<u32>, @builtin(num_workgroups) num_workgroups: vec3<u32>) {
+    if invocation_id.x >= dims.width || invocation_id.y >= dims.height {
+        return;
+    }
     let location = vec2<i32>(i32(invocation_id.x), i32(invocation_id.y));
-    let idx = invocation_id.y * u32(100) + invocation_id.x;
-    let value = input_array[idx];
+    let idx = invocation_id.y * dims.width + invocation_id.x;
+    var value = 0u;
+    if idx < dims.lines {
+        value = input_array[idx];
+    }
     let r = u32((value >> u32(16))) & u32(0xFF);
     let g = u32(value >> u32(8)) & u32(0xFF);
     let b = u32((value)) & u32(0xFF);
@@ -19,11 +49,46 @@ fn init(@builtin(global_invocation_id) invocation_id: vec3<u32>, @builtin(num_wo
     textureStore(texture, location, color);
 }
 
+// One invocation per line; the first line of each elf adds up its calories.
+@compute @workgroup_size(64, 1, 1)
+fn sum(@builtin(global_invocation_id) invocation_id: vec3<u32>) {
+    let len = dims.lines;
+    let start = invocation_id.x;
+    if start >= len {
+        return;
+    }
 
-@compute @workgroup_size(8, 8, 1)
-fn update(@builtin(global_invocation_id) invocation_id: vec3<u32>) {
-    // let color = vec2<f32>(position.x % 256.0 / 256.0, position.y % 129.0 / 129.0);
-    // picked_color[0] = color.r;
-    // picked_color[1] = color.g;
-    // picked_color[2] = 0.0;
-} 
\ No newline at end of file
+    var total = 0u;
+    if !is_blank(start) && (start == 0u || is_blank(start - 1u)) {
+        var idx = start;
+        loop {
+            if idx >= len || is_blank(idx) {
+                break;
+            }
+            total += input_array[idx];
+            idx += 1u;
+        }
+    }
+    elf_totals[start] = total;
+}
+
+// A single invocation keeping the three largest totals.
+@compute @workgroup_size(1, 1, 1)
+fn top3() {
+    var top = vec3<u32>(0u);
+    let len = dims.lines;
+    for (var idx = 0u; idx < len; idx += 1u) {
+        let total = elf_totals[idx];
+        if total > top.x {
+            top = vec3<u32>(total, top.x, top.y);
+        } else if total > top.y {
+            top = vec3<u32>(top.x, total, top.y);
+        } else if total > top.z {
+            top.z = total;
+        }
+    }
+
+    output_array[0] = top.x;
+    output_array[1] = top.y;
+    output_array[2] = top.z;
+}
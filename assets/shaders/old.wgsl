@@ -54,14 +54,14 @@ fn sums(location: vec2<i32>) -> vec4<f32> {
         return current;
     } else if left[3] == 0.0 {
         // add 
-        let current_bytes = ivec4(current * 255);
-        let right_bytes = ivec4(right * 255);
+        let current_bytes = vec4<i32>(current * 255.0);
+        let right_bytes = vec4<i32>(right * 255.0);
 
-        let current_integer = (current_bytes.r << 16) | (current_bytes.g << 8) | current_bytes.a;
-        let right_integer = (right_bytes.r << 16) | (right_bytes.g << 8) | right_bytes.a;
+        let current_integer = (current_bytes.r << 16u) | (current_bytes.g << 8u) | current_bytes.a;
+        let right_integer = (right_bytes.r << 16u) | (right_bytes.g << 8u) | right_bytes.a;
         let next = current_integer + right_integer;
-        let next_bytes = ivec4((next >> 16) && 0xFF, (next >> 8) && 0xFF, next && 0xFF, 255);
-        return fvec4(next_bytes / 255);
+        let next_bytes = vec4<i32>((next >> 16u) & 0xFF, (next >> 8u) & 0xFF, next & 0xFF, 255);
+        return vec4<f32>(next_bytes) / 255.0;
     } else {
         // replace with right
         return right;
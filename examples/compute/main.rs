@@ -16,6 +16,8 @@ use bevy::{
     ui,
 };
 
+use advent::shaders;
+
 use std::borrow::{BorrowMut, Cow};
 use std::sync::{Arc, Mutex};
 
@@ -231,44 +233,9 @@ impl FromWorld for GradientPipeline {
                 .resource::<RenderDevice>()
                 .create_bind_group_layout(&BindGroupLayoutDescriptor {
                     label: None,
-                    entries: &[
-                        BindGroupLayoutEntry {
-                            binding: 0,
-                            visibility: ShaderStages::COMPUTE,
-                            ty: BindingType::StorageTexture {
-                                access: StorageTextureAccess::ReadWrite,
-                                format: TextureFormat::Rgba8Unorm,
-                                view_dimension: TextureViewDimension::D2,
-                            },
-                            count: None,
-                        },
-                        BindGroupLayoutEntry {
-                            binding: 1,
-                            visibility: ShaderStages::COMPUTE,
-                            ty: BindingType::Buffer {
-                                ty: BufferBindingType::Storage { read_only: false },
-                                has_dynamic_offset: false,
-                                min_binding_size: None,
-                            },
-                            count: None,
-                        },
-                        BindGroupLayoutEntry {
-                            binding: 2,
-                            visibility: ShaderStages::COMPUTE,
-                            ty: BindingType::Buffer {
-                                ty: BufferBindingType::Uniform,
-                                has_dynamic_offset: false,
-                                min_binding_size: BufferSize::new(
-                                    std::mem::size_of::<Vec2>() as u64
-                                ),
-                            },
-                            count: None,
-                        },
-                    ],
+                    entries: &shaders::GRADIENT.layout_entries(),
                 });
-        let shader = world
-            .resource::<AssetServer>()
-            .load("shaders/compute_buffer.wgsl");
+        let shader = world.resource::<AssetServer>().load(shaders::GRADIENT.path);
         let mut pipeline_cache = world.resource_mut::<PipelineCache>();
         let init_pipeline = pipeline_cache.queue_compute_pipeline(ComputePipelineDescriptor {
             label: None,
@@ -0,0 +1,454 @@
+//! The compute shader backend. `day1.wgsl` paints the calories into a
+//! texture, adds up each elf's calories and keeps the three largest totals,
+//! which are read back and printed like the CPU solvers' answers. Without a
+//! GPU adapter the same kernel runs on the CPU instead.
+
+use bevy::{
+    app::AppExit,
+    prelude::*,
+    render::{
+        extract_resource::{ExtractResource, ExtractResourcePlugin},
+        render_asset::RenderAssets,
+        render_graph::{self, RenderGraph},
+        render_resource::*,
+        renderer::{RenderContext, RenderDevice},
+        settings::WgpuSettings,
+        RenderApp, RenderStage,
+    },
+};
+
+use std::borrow::Cow;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+
+use advent::days::y2022::day1::{self, KernelInput, KernelLayout};
+use advent::error::{self, Error};
+use advent::{args, runner, shaders};
+
+/// The three largest totals.
+const OUTPUT_SIZE: u64 = 3 * std::mem::size_of::<u32>() as u64;
+
+pub fn app(opt: &args::Opt) {
+    let content = match error::read_input(&opt.input_path(2022, 1)) {
+        Ok(content) => content,
+        Err(err) => return runner::report(Err(err)),
+    };
+
+    if !has_adapter() {
+        eprintln!(
+            "no GPU adapter found; running {} on the CPU",
+            shaders::DAY1.path
+        );
+        let top = day1::top3_on_cpu(&content).map_err(Error::from_solver);
+        return runner::report(top.map(|top| answers(opt.parts(), top)));
+    }
+    let prepared = day1::kernel_input(&content)
+        .and_then(|input| Ok((KernelLayout::new(input.calories.len())?, input)));
+    let (layout, input) = match prepared {
+        Ok(prepared) => prepared,
+        Err(err) => return runner::report(Err(Error::from_solver(err))),
+    };
+
+    App::new()
+        .insert_resource(ClearColor(Color::BLACK))
+        .insert_resource(InputFile(input))
+        .insert_resource(Day1Layout(layout))
+        .insert_resource(Parts(opt.parts()))
+        .add_plugins(
+            DefaultPlugins
+                .set(ImagePlugin::default_nearest())
+                .set(AssetPlugin {
+                    watch_for_changes: true,
+                    ..default()
+                })
+                .set(WindowPlugin {
+                    window: WindowDescriptor {
+                        // uncomment for unthrottled FPS
+                        // present_mode: bevy::window::PresentMode::AutoNoVsync,
+                        ..default()
+                    },
+                    ..default()
+                }),
+        )
+        .add_plugin(Day1ComputePlugin)
+        .add_startup_system(setup)
+        .run();
+}
+
+/// Whether Bevy will find an adapter on the backends it tries, since it
+/// panics when there is none.
+fn has_adapter() -> bool {
+    let backends = WgpuSettings::default()
+        .backends
+        .unwrap_or(wgpu::Backends::PRIMARY);
+    wgpu::Instance::new(backends)
+        .enumerate_adapters(backends)
+        .next()
+        .is_some()
+}
+
+fn answers(parts: &[u8], top: [u32; 3]) -> Vec<(u8, String)> {
+    let (part1, part2) = day1::answers(top);
+    parts
+        .iter()
+        .map(|&part| (part, if part == 1 { part1 } else { part2 }.to_string()))
+        .collect()
+}
+
+/// The calorie lines as `day1.wgsl` reads them.
+#[derive(Resource)]
+pub struct InputFile(KernelInput);
+
+/// Sizes of the buffers, texture and dispatches, in both worlds.
+#[derive(Resource, Clone, Copy)]
+struct Day1Layout(KernelLayout);
+
+/// Which answers to print.
+#[derive(Resource)]
+struct Parts(&'static [u8]);
+
+/// Shared between the main and render worlds: the render graph flags when the
+/// totals have been copied out, and the render world leaves them here for the
+/// main world to print.
+#[derive(Resource, Clone, Default)]
+struct Readback {
+    copied: Arc<AtomicBool>,
+    top: Arc<Mutex<Option<[u32; 3]>>>,
+}
+
+// map the copied totals once the frame that copied them has been submitted
+fn read_back(
+    buffers: Res<SolutionBuffers>,
+    render_device: Res<RenderDevice>,
+    readback: Res<Readback>,
+) {
+    if !readback.copied.swap(false, Ordering::AcqRel) {
+        return;
+    }
+    let slice = buffers.staging_buffer.slice(..);
+    render_device.map_buffer(&slice, MapMode::Read, |result| {
+        result.expect("couldn't map the output buffer")
+    });
+    render_device.poll(wgpu::Maintain::Wait);
+    let top: Vec<u32> = bevy::core::cast_slice(&slice.get_mapped_range()).to_vec();
+    buffers.staging_buffer.unmap();
+    *readback.top.lock().unwrap() = Some([top[0], top[1], top[2]]);
+}
+
+fn report_answers(readback: Res<Readback>, parts: Res<Parts>, mut exit: EventWriter<AppExit>) {
+    if let Some(top) = readback.top.lock().unwrap().take() {
+        runner::report(Ok(answers(parts.0, top)));
+        exit.send(AppExit);
+    }
+}
+
+fn setup(mut commands: Commands, mut images: ResMut<Assets<Image>>, layout: Res<Day1Layout>) {
+    let KernelLayout { width, height, .. } = layout.0;
+    let mut image = Image::new_fill(
+        Extent3d {
+            width,
+            height,
+            depth_or_array_layers: 1,
+        },
+        TextureDimension::D2,
+        &[0, 0, 0, 255],
+        TextureFormat::Rgba8Unorm,
+    );
+    image.texture_descriptor.usage =
+        TextureUsages::COPY_DST | TextureUsages::STORAGE_BINDING | TextureUsages::TEXTURE_BINDING;
+    let image = images.add(image);
+
+    commands.spawn(SpriteBundle {
+        transform: Transform::from_xyz(0., -500., 0.),
+        sprite: Sprite {
+            custom_size: Some(Vec2::new(1000.0, 1000.0 * height as f32 / width as f32)),
+            ..default()
+        },
+        texture: image.clone(),
+        ..default()
+    });
+    commands.spawn(Camera2dBundle::default());
+
+    commands.insert_resource(Day1Image(image));
+}
+
+pub struct Day1ComputePlugin;
+
+impl Plugin for Day1ComputePlugin {
+    fn build(&self, app: &mut App) {
+        let render_device = app.world.resource::<RenderDevice>();
+        let input = app.world.resource::<InputFile>();
+        let layout = *app.world.resource::<Day1Layout>();
+
+        let input_buffer = render_device.create_buffer_with_data(&BufferInitDescriptor {
+            label: Some("Input File Buffer"),
+            contents: bevy::core::cast_slice(input.0.calories.as_slice()),
+            usage: BufferUsages::STORAGE,
+        });
+        let blank_lines_buffer = render_device.create_buffer_with_data(&BufferInitDescriptor {
+            label: Some("Blank Lines Buffer"),
+            contents: bevy::core::cast_slice(input.0.blank_lines.as_slice()),
+            usage: BufferUsages::STORAGE,
+        });
+        let dims_buffer = render_device.create_buffer_with_data(&BufferInitDescriptor {
+            label: Some("Dims Buffer"),
+            contents: bevy::core::cast_slice(&layout.0.dims()),
+            usage: BufferUsages::UNIFORM,
+        });
+        let output_buffer = render_device.create_buffer(&BufferDescriptor {
+            label: Some("Output Buffer"),
+            size: OUTPUT_SIZE,
+            usage: BufferUsages::STORAGE | BufferUsages::COPY_SRC,
+            mapped_at_creation: false,
+        });
+        let totals_buffer = render_device.create_buffer(&BufferDescriptor {
+            label: Some("Elf Totals Buffer"),
+            size: layout.0.buffer_size(),
+            usage: BufferUsages::STORAGE,
+            mapped_at_creation: false,
+        });
+        let staging_buffer = render_device.create_buffer(&BufferDescriptor {
+            label: Some("Output Staging Buffer"),
+            size: OUTPUT_SIZE,
+            usage: BufferUsages::MAP_READ | BufferUsages::COPY_DST,
+            mapped_at_creation: false,
+        });
+
+        let solution_buffers = SolutionBuffers {
+            input_buffer,
+            blank_lines_buffer,
+            output_buffer,
+            totals_buffer,
+            staging_buffer,
+            dims_buffer,
+        };
+
+        let readback = Readback::default();
+        app.insert_resource(readback.clone())
+            .add_system(report_answers);
+        app.add_plugin(ExtractResourcePlugin::<Day1Image>::default());
+
+        let render_app = app.sub_app_mut(RenderApp);
+        render_app
+            .init_resource::<Day1Pipeline>()
+            .insert_resource(solution_buffers)
+            .insert_resource(readback)
+            .insert_resource(layout)
+            .add_system_to_stage(RenderStage::Queue, queue_bind_group)
+            .add_system_to_stage(RenderStage::Cleanup, read_back);
+
+        let mut render_graph = render_app.world.resource_mut::<RenderGraph>();
+        render_graph.add_node("day1", Day1Node::default());
+        render_graph
+            .add_node_edge("day1", bevy::render::main_graph::node::CAMERA_DRIVER)
+            .unwrap();
+    }
+}
+
+#[derive(Resource)]
+struct SolutionBuffers {
+    input_buffer: Buffer,
+    blank_lines_buffer: Buffer,
+    output_buffer: Buffer,
+    totals_buffer: Buffer,
+    staging_buffer: Buffer,
+    dims_buffer: Buffer,
+}
+
+#[derive(Resource, Clone, Deref, ExtractResource)]
+struct Day1Image(Handle<Image>);
+
+#[derive(Resource)]
+struct Day1ImageBindGroup(BindGroup);
+
+fn queue_bind_group(
+    mut commands: Commands,
+    pipeline: Res<Day1Pipeline>,
+    gpu_images: Res<RenderAssets<Image>>,
+    day1_image: Res<Day1Image>,
+    render_device: Res<RenderDevice>,
+    buffers: Res<SolutionBuffers>,
+) {
+    let view = &gpu_images[&day1_image.0];
+    let bind_group = render_device.create_bind_group(&BindGroupDescriptor {
+        label: None,
+        layout: &pipeline.texture_bind_group_layout,
+        entries: &[
+            BindGroupEntry {
+                binding: 0,
+                resource: BindingResource::TextureView(&view.texture_view),
+            },
+            BindGroupEntry {
+                binding: 1,
+                resource: BindingResource::Buffer(buffers.input_buffer.as_entire_buffer_binding()),
+            },
+            BindGroupEntry {
+                binding: 2,
+                resource: BindingResource::Buffer(buffers.output_buffer.as_entire_buffer_binding()),
+            },
+            BindGroupEntry {
+                binding: 3,
+                resource: BindingResource::Buffer(buffers.totals_buffer.as_entire_buffer_binding()),
+            },
+            BindGroupEntry {
+                binding: 4,
+                resource: BindingResource::Buffer(buffers.dims_buffer.as_entire_buffer_binding()),
+            },
+            BindGroupEntry {
+                binding: 5,
+                resource: BindingResource::Buffer(
+                    buffers.blank_lines_buffer.as_entire_buffer_binding(),
+                ),
+            },
+        ],
+    });
+    commands.insert_resource(Day1ImageBindGroup(bind_group));
+}
+
+#[derive(Resource)]
+pub struct Day1Pipeline {
+    texture_bind_group_layout: BindGroupLayout,
+    init_pipeline: CachedComputePipelineId,
+    sum_pipeline: CachedComputePipelineId,
+    top3_pipeline: CachedComputePipelineId,
+}
+
+impl FromWorld for Day1Pipeline {
+    fn from_world(world: &mut World) -> Self {
+        let texture_bind_group_layout =
+            world
+                .resource::<RenderDevice>()
+                .create_bind_group_layout(&BindGroupLayoutDescriptor {
+                    label: None,
+                    entries: &shaders::DAY1.layout_entries(),
+                });
+        let shader = world.resource::<AssetServer>().load(shaders::DAY1.path);
+        let mut pipeline_cache = world.resource_mut::<PipelineCache>();
+        let mut queue = |entry_point: &'static str| {
+            pipeline_cache.queue_compute_pipeline(ComputePipelineDescriptor {
+                label: None,
+                layout: Some(vec![texture_bind_group_layout.clone()]),
+                shader: shader.clone(),
+                shader_defs: vec![],
+                entry_point: Cow::from(entry_point),
+            })
+        };
+        let (init_pipeline, sum_pipeline, top3_pipeline) =
+            (queue("init"), queue("sum"), queue("top3"));
+
+        Day1Pipeline {
+            texture_bind_group_layout,
+            init_pipeline,
+            sum_pipeline,
+            top3_pipeline,
+        }
+    }
+}
+
+enum Day1State {
+    Loading,
+    /// Paint the input and add up each elf's calories.
+    Init,
+    /// Find the three largest totals and copy them out.
+    Update,
+    Done,
+}
+
+struct Day1Node {
+    state: Day1State,
+}
+
+impl Default for Day1Node {
+    fn default() -> Self {
+        Self {
+            state: Day1State::Loading,
+        }
+    }
+}
+
+impl render_graph::Node for Day1Node {
+    fn update(&mut self, world: &mut World) {
+        let pipeline = world.resource::<Day1Pipeline>();
+        let pipeline_cache = world.resource::<PipelineCache>();
+
+        // once every pipeline has loaded, run each stage for one frame
+        self.state = match self.state {
+            Day1State::Loading => {
+                let loaded = [
+                    pipeline.init_pipeline,
+                    pipeline.sum_pipeline,
+                    pipeline.top3_pipeline,
+                ]
+                .into_iter()
+                .all(|id| {
+                    matches!(
+                        pipeline_cache.get_compute_pipeline_state(id),
+                        CachedPipelineState::Ok(_)
+                    )
+                });
+                if loaded {
+                    Day1State::Init
+                } else {
+                    Day1State::Loading
+                }
+            }
+            Day1State::Init => Day1State::Update,
+            Day1State::Update | Day1State::Done => Day1State::Done,
+        };
+    }
+
+    fn run(
+        &self,
+        _graph: &mut render_graph::RenderGraphContext,
+        render_context: &mut RenderContext,
+        world: &World,
+    ) -> Result<(), render_graph::NodeRunError> {
+        let texture_bind_group = &world.resource::<Day1ImageBindGroup>().0;
+        let pipeline_cache = world.resource::<PipelineCache>();
+        let pipeline = world.resource::<Day1Pipeline>();
+        let layout = world.resource::<Day1Layout>().0;
+        let compute = |id| pipeline_cache.get_compute_pipeline(id).unwrap();
+
+        {
+            let mut pass = render_context
+                .command_encoder
+                .begin_compute_pass(&ComputePassDescriptor::default());
+
+            pass.set_bind_group(0, texture_bind_group, &[]);
+
+            // select the pipelines based on the current state
+            match self.state {
+                Day1State::Loading | Day1State::Done => {}
+                Day1State::Init => {
+                    pass.set_pipeline(compute(pipeline.init_pipeline));
+                    let [x, y, z] = layout.init_workgroups();
+                    pass.dispatch_workgroups(x, y, z);
+                    pass.set_pipeline(compute(pipeline.sum_pipeline));
+                    let [x, y, z] = layout.sum_workgroups();
+                    pass.dispatch_workgroups(x, y, z);
+                }
+                Day1State::Update => {
+                    pass.set_pipeline(compute(pipeline.top3_pipeline));
+                    pass.dispatch_workgroups(1, 1, 1);
+                }
+            }
+        }
+
+        if let Day1State::Update = self.state {
+            let buffers = world.resource::<SolutionBuffers>();
+            render_context.command_encoder.copy_buffer_to_buffer(
+                &buffers.output_buffer,
+                0,
+                &buffers.staging_buffer,
+                0,
+                OUTPUT_SIZE,
+            );
+            world
+                .resource::<Readback>()
+                .copied
+                .store(true, Ordering::Release);
+        }
+
+        Ok(())
+    }
+}
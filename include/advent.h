@@ -0,0 +1,62 @@
+#ifndef ADVENT_H
+#define ADVENT_H
+
+/* Generated by cbindgen from src/ffi.rs. Run `UPDATE_HEADER=1 cargo test --test ffi` after changing it. */
+
+#include <stddef.h>
+#include <stdint.h>
+
+/**
+ * Status codes returned by [`advent_solve`].
+ */
+enum AdventStatus {
+  ADVENT_STATUS_OK = 0,
+  ADVENT_STATUS_NULL_POINTER = 1,
+  ADVENT_STATUS_INVALID_UTF8 = 2,
+  ADVENT_STATUS_UNKNOWN_SOLVER = 3,
+  ADVENT_STATUS_SOLVE_FAILED = 4,
+  ADVENT_STATUS_BUFFER_TOO_SMALL = 5,
+  ADVENT_STATUS_PANICKED = 6,
+};
+typedef int32_t AdventStatus;
+
+/**
+ * Solves `part` of `day` of the latest year for the `input_len` bytes of
+ * UTF-8 at `input_ptr`.
+ *
+ * `*out_len` holds the capacity of `out_buf` on entry. On success the answer
+ * is written to `out_buf` NUL-terminated and `*out_len` is set to its length
+ * without the NUL. `SolveFailed` writes the error message the same way,
+ * truncated to fit. `BufferTooSmall` leaves `out_buf` untouched and sets
+ * `*out_len` to the capacity the answer needs, NUL included.
+ *
+ * # Safety
+ *
+ * `input_ptr` must point to `input_len` readable bytes, `out_buf` to
+ * `*out_len` writable bytes, and `out_len` must be valid for reads and
+ * writes.
+ */
+AdventStatus advent_solve(uint32_t day,
+                          uint32_t part,
+                          const uint8_t *input_ptr,
+                          size_t input_len,
+                          uint8_t *out_buf,
+                          size_t *out_len);
+
+/**
+ * [`advent_solve`] for `day` of `year`, which fails with `UnknownSolver`
+ * unless that year is solved.
+ *
+ * # Safety
+ *
+ * As for [`advent_solve`].
+ */
+AdventStatus advent_solve_year(uint32_t year,
+                               uint32_t day,
+                               uint32_t part,
+                               const uint8_t *input_ptr,
+                               size_t input_len,
+                               uint8_t *out_buf,
+                               size_t *out_len);
+
+#endif /* ADVENT_H */
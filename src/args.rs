@@ -1,15 +1,190 @@
 use std::path::PathBuf;
+use std::sync::OnceLock;
+use std::time::Duration;
 use structopt::StructOpt;
 
-#[derive(Debug, StructOpt)]
-#[structopt(name = "advent", about = "Advent of Code 2022.")]
+use crate::days::{self, Backend};
+use crate::describe::DEFAULT_BASE_URL;
+use crate::inputs;
+
+#[derive(Debug, Clone, StructOpt)]
+#[structopt(name = "advent", about = solver_about())]
 pub struct Opt {
     #[structopt(long)]
     pub part2: bool,
 
+    /// Run both parts off a single parse of the input
+    #[structopt(long, conflicts_with = "part2")]
+    pub both: bool,
+
+    /// Run on the compute shader backend instead of the CPU (builds with the
+    /// gpu feature only)
     #[structopt(long)]
     pub compute: bool,
-    /// Input file
+
+    /// Give up on the part after this many seconds
+    #[structopt(long, parse(try_from_str = parse_seconds))]
+    pub timeout: Option<Duration>,
+
+    /// Input file, defaults to the day's input under inputs/{year}
     #[structopt(parse(from_os_str))]
-    pub input: PathBuf,
+    pub input: Option<PathBuf>,
+}
+
+impl Opt {
+    /// The input file given, or the stored input for `day` of `year`.
+    pub fn input_path(&self, year: u16, day: u8) -> PathBuf {
+        self.input
+            .clone()
+            .unwrap_or_else(|| inputs::path(year, day))
+    }
+
+    /// The parts `--part2` and `--both` select.
+    pub fn parts(&self) -> &'static [u8] {
+        if self.both {
+            &[1, 2]
+        } else if self.part2 {
+            &[2]
+        } else {
+            &[1]
+        }
+    }
+
+    pub fn backend(&self) -> Backend {
+        if self.compute {
+            Backend::Compute
+        } else {
+            Backend::Cpu
+        }
+    }
+}
+
+/// `days::LATEST_YEAR`, the `--year` default.
+fn latest_year() -> &'static str {
+    static YEAR: OnceLock<String> = OnceLock::new();
+    YEAR.get_or_init(|| days::LATEST_YEAR.to_string())
+}
+
+fn solver_about() -> &'static str {
+    static ABOUT: OnceLock<String> = OnceLock::new();
+    ABOUT.get_or_init(|| format!("Advent of Code {}.", days::LATEST_YEAR))
+}
+
+fn tools_about() -> &'static str {
+    static ABOUT: OnceLock<String> = OnceLock::new();
+    ABOUT.get_or_init(|| format!("Advent of Code {} tools.", days::LATEST_YEAR))
+}
+
+/// A positive, finite number of seconds.
+fn parse_seconds(arg: &str) -> Result<Duration, String> {
+    let seconds = arg.parse::<f64>().map_err(|err| err.to_string())?;
+    if seconds <= 0.0 {
+        return Err("must be positive".to_owned());
+    }
+    Duration::try_from_secs_f64(seconds).map_err(|err| err.to_string())
+}
+
+/// A frame rate whose frames last a representable time.
+fn parse_fps(arg: &str) -> Result<f64, String> {
+    let fps = arg.parse::<f64>().map_err(|err| err.to_string())?;
+    parse_seconds(&(1.0 / fps).to_string())?;
+    Ok(fps)
+}
+
+#[derive(Debug, StructOpt)]
+#[structopt(name = "advent", about = tools_about())]
+pub enum Command {
+    /// Check an input file against a day's structural invariants
+    Lint {
+        day: u8,
+        #[structopt(long, default_value = latest_year())]
+        year: u16,
+        /// Input file
+        #[structopt(parse(from_os_str))]
+        input: PathBuf,
+    },
+    /// List the solved days and the backends each one supports
+    List,
+    /// Serve the solvers over HTTP at POST /day/N/part/P
+    Serve {
+        #[structopt(long, default_value = "8022")]
+        port: u16,
+        /// Give up on a request after this many seconds
+        #[structopt(long, default_value = "30", parse(try_from_str = parse_seconds))]
+        timeout: Duration,
+    },
+    /// Guess which day an input file belongs to
+    Identify {
+        /// Input file
+        #[structopt(parse(from_os_str))]
+        file: PathBuf,
+    },
+    /// Show a day's puzzle text and save its example inputs
+    Describe {
+        day: u8,
+        #[structopt(long, default_value = latest_year())]
+        year: u16,
+        /// Site to fetch puzzle pages from
+        #[structopt(long, default_value = DEFAULT_BASE_URL)]
+        base_url: String,
+        /// Where fetched pages are kept
+        #[structopt(long, default_value = "puzzles", parse(from_os_str))]
+        cache_dir: PathBuf,
+        /// Where example inputs are written, one directory per year
+        #[structopt(long, default_value = "inputs", parse(from_os_str))]
+        inputs: PathBuf,
+    },
+    /// Watch a day's simulation in the terminal, or save it as a GIF
+    Play {
+        day: u8,
+        #[structopt(long, default_value = latest_year())]
+        year: u16,
+        /// Input file, defaults to the day's input under inputs/{year}
+        #[structopt(parse(from_os_str))]
+        input: Option<PathBuf>,
+        /// Frames per second to start at
+        #[structopt(long, default_value = "10", parse(try_from_str = parse_fps))]
+        fps: f64,
+        /// Write the frames to this GIF instead of playing them
+        #[structopt(long, parse(from_os_str))]
+        save: Option<PathBuf>,
+    },
+    /// Summarize a private leaderboard from its exported JSON
+    Leaderboard {
+        /// Exported leaderboard JSON
+        #[structopt(parse(from_os_str))]
+        file: PathBuf,
+    },
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn timeouts() {
+        assert_eq!(parse_seconds("1.5"), Ok(Duration::from_millis(1500)));
+        for bad in ["0", "-1", "1e300", "inf", "NaN", "soon"] {
+            assert!(parse_seconds(bad).is_err(), "{}", bad);
+        }
+        assert!(Opt::from_iter_safe(["day4", "--timeout", "1e300"]).is_err());
+
+        assert_eq!(parse_fps("0.5"), Ok(0.5));
+        for bad in ["0", "-10", "1e-300", "NaN"] {
+            assert!(parse_fps(bad).is_err(), "{}", bad);
+        }
+    }
+
+    #[test]
+    fn years_default_to_the_latest() {
+        let command = Command::from_iter_safe(["advent", "lint", "4", "input.txt"]).unwrap();
+        assert!(matches!(command, Command::Lint { year, .. } if year == days::LATEST_YEAR));
+        let command = Command::from_iter_safe(["advent", "play", "9", "--year", "2021"]).unwrap();
+        assert!(matches!(command, Command::Play { year: 2021, .. }));
+
+        let mut help = Vec::new();
+        Command::clap().write_help(&mut help).unwrap();
+        let help = String::from_utf8(help).unwrap();
+        assert!(help.contains(&format!("Advent of Code {} tools.", days::LATEST_YEAR)));
+    }
 }
@@ -0,0 +1,296 @@
+use std::fmt;
+
+use anyhow::Result;
+
+use crate::lint::Violation;
+use crate::runner::Cancel;
+use crate::visualize::Frame;
+
+/// A solver for one part of a day, producing the answer as it would be typed
+/// into the puzzle page.
+pub type Solver = fn(&str, &Cancel) -> Result<String>;
+
+/// Solves both parts of a day from a single parse of the input.
+pub type BothSolver = fn(&str, &Cancel) -> Result<(String, String)>;
+
+/// Parses a day's input, throwing the model away; fails where its solvers
+/// would reject the input.
+pub type Parser = fn(&str) -> Result<()>;
+
+/// Checks a day's input against its structural invariants, reporting every
+/// violation.
+pub type Linter = fn(&str) -> Vec<Violation>;
+
+/// Renders a day's input as the frames of an animation.
+pub type Visualizer = fn(&str) -> Result<Vec<Frame>>;
+
+// Each day module has a `parse` function building the model both of its
+// parts run on, and `part1` and `part2` taking a reference to that model.
+
+macro_rules! solver {
+    ($day:ident::$part:ident) => {
+        |input, cancel| {
+            let model = $day::parse(input)?;
+            $day::$part(&model, cancel).map(|answer| answer.to_string())
+        }
+    };
+}
+
+macro_rules! parser {
+    ($day:ident) => {
+        |input| $day::parse(input).map(drop)
+    };
+}
+
+macro_rules! both {
+    ($day:ident) => {
+        |input, cancel| {
+            let model = $day::parse(input)?;
+            Ok((
+                $day::part1(&model, cancel)?.to_string(),
+                $day::part2(&model, cancel)?.to_string(),
+            ))
+        }
+    };
+}
+
+macro_rules! visualizer {
+    ($day:ident) => {
+        Some(|input| {
+            use crate::visualize::Visualize;
+            Ok($day::parse(input)?.frames().collect())
+        })
+    };
+}
+
+pub mod y2022;
+
+/// Ways a day's solution can be executed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Backend {
+    /// Plain Rust on the CPU.
+    Cpu,
+    /// A Bevy app that runs a compute shader on the GPU.
+    Compute,
+}
+
+impl fmt::Display for Backend {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Backend::Cpu => write!(f, "cpu"),
+            Backend::Compute => write!(f, "compute"),
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct DayInfo {
+    pub day: u8,
+    pub title: &'static str,
+    pub parse: Parser,
+    pub backends: &'static [Backend],
+    pub part1: Solver,
+    pub part2: Solver,
+    pub both: BothSolver,
+    /// Days without lint rules have `None`.
+    pub lint: Option<Linter>,
+    /// Days without an animation have `None`.
+    pub visualize: Option<Visualizer>,
+}
+
+impl DayInfo {
+    pub fn supports(&self, backend: Backend) -> bool {
+        self.backends.contains(&backend)
+    }
+
+    pub fn part(&self, part: u8) -> Option<Solver> {
+        match part {
+            1 => Some(self.part1),
+            2 => Some(self.part2),
+            _ => None,
+        }
+    }
+}
+
+/// The days solved for one event.
+#[derive(Debug)]
+pub struct Year {
+    pub year: u16,
+    pub days: &'static [DayInfo],
+}
+
+pub const YEARS: &[Year] = &[Year {
+    year: 2022,
+    days: y2022::DAYS,
+}];
+
+/// The year tools default to when none is given.
+pub const LATEST_YEAR: u16 = 2022;
+
+pub fn info(year: u16, day: u8) -> Option<&'static DayInfo> {
+    YEARS
+        .iter()
+        .find(|entry| entry.year == year)?
+        .days
+        .iter()
+        .find(|info| info.day == day)
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Unsupported {
+    pub year: u16,
+    pub day: u8,
+    pub backend: Backend,
+}
+
+impl fmt::Display for Unsupported {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match info(self.year, self.day) {
+            Some(info) => write!(
+                f,
+                "{} day {} has no {} backend (supported: {})",
+                self.year,
+                self.day,
+                self.backend,
+                info.backends
+                    .iter()
+                    .map(Backend::to_string)
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            ),
+            None => write!(f, "{} day {} is not solved yet", self.year, self.day),
+        }
+    }
+}
+
+impl std::error::Error for Unsupported {}
+
+/// Fails unless `day` of `year` exists and declares `backend`.
+pub fn require(year: u16, day: u8, backend: Backend) -> Result<&'static DayInfo, Unsupported> {
+    match info(year, day) {
+        Some(info) if info.supports(backend) => Ok(info),
+        _ => Err(Unsupported { year, day, backend }),
+    }
+}
+
+pub fn solver(year: u16, day: u8, part: u8) -> Option<Solver> {
+    info(year, day)?.part(part)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn every_day_runs_on_cpu() {
+        for year in YEARS {
+            for (idx, info) in year.days.iter().enumerate() {
+                assert_eq!(info.day as usize, idx + 1);
+                assert!(
+                    info.supports(Backend::Cpu),
+                    "{} day {}",
+                    year.year,
+                    info.day
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn example_answers() {
+        let cancel = Cancel::new();
+        let examples = [
+            (
+                4,
+                include_str!("../../inputs/2022/day4_example.txt"),
+                "2",
+                "4",
+            ),
+            (
+                5,
+                include_str!("../../inputs/2022/day5_example.txt"),
+                "CMZ",
+                "MCD",
+            ),
+            (
+                8,
+                include_str!("../../inputs/2022/day8_example.txt"),
+                "21",
+                "8",
+            ),
+        ];
+        for (day, input, part1, part2) in examples {
+            assert_eq!(
+                solver(2022, day, 1).unwrap()(input, &cancel).unwrap(),
+                part1
+            );
+            assert_eq!(
+                solver(2022, day, 2).unwrap()(input, &cancel).unwrap(),
+                part2
+            );
+            let both = info(2022, day).unwrap().both;
+            assert_eq!(
+                both(input, &cancel).unwrap(),
+                (part1.to_owned(), part2.to_owned())
+            );
+        }
+        assert!(solver(2022, 1, 3).is_none());
+        assert!(solver(2015, 1, 1).is_none());
+    }
+
+    #[test]
+    fn animated_days() {
+        let visualize = |day| info(2022, day).unwrap().visualize.unwrap();
+        let frames = visualize(5)(include_str!("../../inputs/2022/day5_example.txt")).unwrap();
+        assert_eq!(frames.len(), 5);
+        assert_eq!(frames[4].caption, "move 1 from 1 to 2");
+        assert!(frames[4].to_string().ends_with(" 1   2   3\n"));
+
+        let frames = visualize(9)("R 4\nU 4\n").unwrap();
+        assert_eq!(frames.len(), 3);
+        assert_eq!(frames[2].to_string(), "    H\n    1\n  432\n 5\n6\n");
+        assert!(visualize(9)("?!\n").is_err());
+        assert!(info(2022, 1).unwrap().visualize.is_none());
+    }
+
+    #[test]
+    fn garbage_is_invalid_input() {
+        let cancel = Cancel::new();
+        for info in YEARS.iter().flat_map(|year| year.days) {
+            for part in [1, 2] {
+                let err = info.part(part).unwrap()("?!\n", &cancel).unwrap_err();
+                assert!(
+                    matches!(
+                        crate::error::Error::from_solver(err),
+                        crate::error::Error::Parse(_)
+                    ),
+                    "day {} part {}",
+                    info.day,
+                    part
+                );
+            }
+        }
+        let err = solver(2022, 1, 1).unwrap()("", &cancel).unwrap_err();
+        assert!(matches!(
+            crate::error::Error::from_solver(err),
+            crate::error::Error::Parse(_)
+        ));
+    }
+
+    #[test]
+    fn unsupported_backend() {
+        assert_eq!(
+            require(2022, 1, Backend::Compute).is_ok(),
+            cfg!(feature = "gpu")
+        );
+        let err = require(2022, 2, Backend::Compute).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "2022 day 2 has no compute backend (supported: cpu)"
+        );
+        let err = require(2022, 25, Backend::Cpu).unwrap_err();
+        assert_eq!(err.to_string(), "2022 day 25 is not solved yet");
+        let err = require(2015, 1, Backend::Cpu).unwrap_err();
+        assert_eq!(err.to_string(), "2015 day 1 is not solved yet");
+    }
+}
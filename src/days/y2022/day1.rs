@@ -0,0 +1,245 @@
+use anyhow::{bail, Result};
+
+#[cfg(feature = "gpu")]
+use bevy::prelude::*;
+
+use crate::error::invalid;
+#[cfg(feature = "kernel")]
+use crate::kernel::{Bindings, Kernel, Texture};
+use crate::parse::blocks;
+use crate::runner::Cancel;
+#[cfg(feature = "kernel")]
+use crate::shaders;
+
+pub fn parse(input: &str) -> Result<Elves> {
+    input.parse()
+}
+
+pub fn part1(elves: &Elves, _cancel: &Cancel) -> Result<i32> {
+    elves
+        .0
+        .iter()
+        .copied()
+        .max()
+        .ok_or_else(|| invalid("empty input"))
+}
+
+pub fn part2(elves: &Elves, _cancel: &Cancel) -> Result<i32> {
+    let mut calories = elves.0.clone();
+    calories.sort_by(|a, b| b.cmp(a));
+    Ok(calories.iter().take(3).sum::<i32>())
+}
+
+/// The total calories carried by each elf.
+#[cfg_attr(feature = "gpu", derive(Resource))]
+pub struct Elves(Vec<i32>);
+
+impl std::str::FromStr for Elves {
+    type Err = anyhow::Error;
+
+    fn from_str(content: &str) -> Result<Self, Self::Err> {
+        let calories: Result<Vec<i32>, _> = blocks(content)
+            .map(|nums| nums.lines().map(str::parse::<i32>).sum())
+            .collect();
+
+        Ok(calories.map(Elves)?)
+    }
+}
+
+/// The input as `day1.wgsl` reads it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct KernelInput {
+    /// One word per line, 0 for the blank lines between elves.
+    pub calories: Vec<u32>,
+    /// One bit per line, set for the blank ones, 32 lines to a word.
+    pub blank_lines: Vec<u32>,
+}
+
+/// Splits `input` into lines the way `Elves::from_str` does: lines of only
+/// whitespace separate elves, and every other line is a calorie count.
+pub fn kernel_input(input: &str) -> Result<KernelInput> {
+    let lines: Vec<_> = input.lines().collect();
+    let mut calories = Vec::with_capacity(lines.len());
+    let mut blank_lines = vec![0; lines.len().div_ceil(32)];
+    for (idx, line) in lines.iter().enumerate() {
+        if line.trim().is_empty() {
+            blank_lines[idx / 32] |= 1 << (idx % 32);
+            calories.push(0);
+            continue;
+        }
+        let count = line
+            .parse::<i32>()
+            .map_err(|err| invalid(format!("{}: {:?}", err, line)))?;
+        let count = u32::try_from(count)
+            .map_err(|_| invalid(format!("negative calorie count {:?}", line)))?;
+        calories.push(count);
+    }
+    Ok(KernelInput {
+        calories,
+        blank_lines,
+    })
+}
+
+/// The workgroup sizes of `day1.wgsl`'s `init`, in both dimensions, and
+/// `sum` entry points.
+pub const PAINT_WORKGROUP_SIZE: u32 = 8;
+pub const SUM_WORKGROUP_SIZE: u32 = 64;
+
+/// wgpu's default limit on workgroups in one dimension of a dispatch.
+const MAX_WORKGROUPS: u32 = 65535;
+
+/// How `day1.wgsl`'s buffers, texture and dispatches are sized for an input.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct KernelLayout {
+    /// Input lines, one word each in the input and totals buffers.
+    pub lines: u32,
+    /// The texture the lines are painted into, row by row; as close to
+    /// square as fits them.
+    pub width: u32,
+    pub height: u32,
+}
+
+impl KernelLayout {
+    pub fn new(lines: usize) -> Result<Self> {
+        let max = (MAX_WORKGROUPS * SUM_WORKGROUP_SIZE) as usize;
+        if lines == 0 {
+            return Err(invalid("empty input"));
+        } else if lines > max {
+            bail!("{} lines is more than one dispatch covers ({})", lines, max);
+        }
+        let lines = lines as u32;
+        let mut width = lines.isqrt();
+        if width * width < lines {
+            width += 1;
+        }
+        Ok(KernelLayout {
+            lines,
+            width,
+            height: lines.div_ceil(width),
+        })
+    }
+
+    /// Bytes in each of the input and totals buffers.
+    pub fn buffer_size(&self) -> u64 {
+        self.lines as u64 * std::mem::size_of::<u32>() as u64
+    }
+
+    /// The contents of the `dims` uniform.
+    pub fn dims(&self) -> [u32; 3] {
+        [self.lines, self.width, self.height]
+    }
+
+    /// Workgroups for `init`, covering the texture.
+    pub fn init_workgroups(&self) -> [u32; 3] {
+        [
+            self.width.div_ceil(PAINT_WORKGROUP_SIZE),
+            self.height.div_ceil(PAINT_WORKGROUP_SIZE),
+            1,
+        ]
+    }
+
+    /// Workgroups for `sum`, one invocation per line.
+    pub fn sum_workgroups(&self) -> [u32; 3] {
+        [self.lines.div_ceil(SUM_WORKGROUP_SIZE), 1, 1]
+    }
+}
+
+/// Runs the `sum` and `top3` entry points of `day1.wgsl` on the CPU, for
+/// machines without a GPU. Returns the three largest totals, largest first.
+#[cfg(feature = "kernel")]
+pub fn top3_on_cpu(input: &str) -> Result<[u32; 3]> {
+    let input = kernel_input(input)?;
+    let layout = KernelLayout::new(input.calories.len())?;
+    let kernel = Kernel::parse(shaders::DAY1.source)?;
+    let mut bindings = Bindings::new()
+        // `init` isn't run, but the layout still has the texture.
+        .with_texture(0, Texture::new(1, 1, [0, 0, 0, 255]))
+        .with_u32s(1, &input.calories)
+        .with_u32s(2, &[0; 3])
+        .with_u32s(3, &vec![0; input.calories.len()])
+        .with_u32s(4, &layout.dims())
+        .with_u32s(5, &input.blank_lines);
+    kernel.dispatch("sum", layout.sum_workgroups(), &mut bindings)?;
+    kernel.dispatch("top3", [1, 1, 1], &mut bindings)?;
+
+    let top = bindings.u32s(2).unwrap();
+    Ok([top[0], top[1], top[2]])
+}
+
+/// The part 1 and part 2 answers from the three largest totals.
+pub fn answers(top: [u32; 3]) -> (u32, u32) {
+    (top[0], top.iter().sum())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &str = "1000\n2000\n3000\n\n4000\n\n5000\n6000\n\n7000\n8000\n9000\n\n10000\n";
+
+    #[test]
+    #[cfg(feature = "kernel")]
+    fn kernel_matches_solvers() {
+        assert_eq!(top3_on_cpu(EXAMPLE).unwrap(), [24000, 11000, 10000]);
+        assert_eq!(answers([24000, 11000, 10000]), (24000, 45000));
+
+        let input = std::fs::read_to_string(crate::inputs::path(2022, 1)).unwrap();
+        let elves = parse(&input).unwrap();
+        let (max, top3) = answers(top3_on_cpu(&input).unwrap());
+        assert_eq!(max as i32, part1(&elves, &Cancel::new()).unwrap());
+        assert_eq!(top3 as i32, part2(&elves, &Cancel::new()).unwrap());
+        assert!(top3_on_cpu("").is_err());
+
+        // Elves carrying nothing, and blank lines of spaces, as `parse` reads them.
+        let input = "0\n\n3\n0\n  \n0\n0\n\n\n2\n";
+        let elves = parse(input).unwrap();
+        let (max, top3) = answers(top3_on_cpu(input).unwrap());
+        assert_eq!(max as i32, part1(&elves, &Cancel::new()).unwrap());
+        assert_eq!(top3 as i32, part2(&elves, &Cancel::new()).unwrap());
+        assert_eq!(top3_on_cpu("0\n0\n").unwrap(), [0, 0, 0]);
+    }
+
+    #[test]
+    fn kernel_layout() {
+        let layout = KernelLayout::new(2250).unwrap();
+        assert_eq!((layout.width, layout.height), (48, 47));
+        assert_eq!(layout.buffer_size(), 9000);
+        assert_eq!(layout.dims(), [2250, 48, 47]);
+        assert_eq!(layout.init_workgroups(), [6, 6, 1]);
+        assert_eq!(layout.sum_workgroups(), [36, 1, 1]);
+
+        let square = KernelLayout::new(64).unwrap();
+        assert_eq!((square.width, square.height), (8, 8));
+        assert_eq!(square.init_workgroups(), [1, 1, 1]);
+        assert_eq!(square.sum_workgroups(), [1, 1, 1]);
+        assert_eq!(KernelLayout::new(1).unwrap().dims(), [1, 1, 1]);
+        assert_eq!(KernelLayout::new(3).unwrap().dims(), [3, 2, 2]);
+
+        let max = 65535 * 64;
+        assert_eq!(
+            KernelLayout::new(max).unwrap().sum_workgroups(),
+            [65535, 1, 1]
+        );
+        assert!(KernelLayout::new(max + 1).is_err());
+        assert!(KernelLayout::new(0).is_err());
+    }
+
+    #[test]
+    fn kernel_input_masks_blank_lines() {
+        let input = kernel_input("1\n\n0\n3").unwrap();
+        assert_eq!(input.calories, [1, 0, 0, 3]);
+        assert_eq!(input.blank_lines, [0b10]);
+        let example = kernel_input(EXAMPLE).unwrap();
+        assert_eq!(example.calories.len(), 14);
+        assert_eq!(example.blank_lines, [0b1_0001_0010_1000]);
+        let long = kernel_input(&"1\n\n".repeat(20)).unwrap();
+        assert_eq!(long.blank_lines, [0xaaaa_aaaa, 0xaa]);
+
+        for bad in ["x\n", " 1\n", "4294967295\n"] {
+            assert!(parse(bad).is_err(), "{:?}", bad);
+            assert!(kernel_input(bad).is_err(), "{:?}", bad);
+        }
+        // The shader adds unsigned words.
+        assert!(kernel_input("1\n-1\n").is_err());
+    }
+}
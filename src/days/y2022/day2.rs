@@ -0,0 +1,204 @@
+use anyhow::Result;
+use itertools::Itertools;
+
+use std::collections::HashMap;
+
+use crate::error::invalid;
+use crate::runner::Cancel;
+
+pub fn part1(rounds: &Rounds, _cancel: &Cancel) -> Result<i32> {
+    let (_, score) = rounds.solve1();
+    Ok(score)
+}
+
+pub fn part2(rounds: &Rounds, _cancel: &Cancel) -> Result<i32> {
+    let (_, score) = rounds.solve2();
+    Ok(score)
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum RPS {
+    Rock,
+    Paper,
+    Scissors,
+}
+
+impl PartialOrd for RPS {
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        use RPS::*;
+        match (self, other) {
+            (Rock, Scissors) => Some(std::cmp::Ordering::Greater),
+            (Rock, Paper) => Some(std::cmp::Ordering::Less),
+            (Scissors, Rock) => Some(std::cmp::Ordering::Less),
+            (Scissors, Paper) => Some(std::cmp::Ordering::Greater),
+            (Paper, Scissors) => Some(std::cmp::Ordering::Less),
+            (Paper, Rock) => Some(std::cmp::Ordering::Greater),
+            _ => Some(std::cmp::Ordering::Equal),
+        }
+    }
+}
+
+impl RPS {
+    fn value(self) -> i32 {
+        use RPS::*;
+        match self {
+            Rock => 1,
+            Paper => 2,
+            Scissors => 3,
+        }
+    }
+
+    fn lose(self) -> RPS {
+        use RPS::*;
+        match self {
+            Rock => Scissors,
+            Paper => Rock,
+            Scissors => Paper,
+        }
+    }
+
+    fn win(self) -> RPS {
+        use RPS::*;
+        match self {
+            Rock => Paper,
+            Paper => Scissors,
+            Scissors => Rock,
+        }
+    }
+
+    fn draw(self) -> RPS {
+        self
+    }
+
+    fn result(self, other: RPS) -> i32 {
+        match (self, other) {
+            (x, y) if x == y => 3 + self.value(),
+            (x, y) if x > y => 6 + self.value(),
+            (x, y) if x < y => self.value(),
+            _ => unreachable!("No result for {:?} / {:?}", self, other),
+        }
+    }
+}
+
+/// Their shape and the letter in the second column, for each round.
+pub struct Rounds<'a>(Vec<(RPS, &'a str)>);
+
+pub fn parse(content: &str) -> Result<Rounds<'_>> {
+    use RPS::*;
+    let rounds = content
+        .lines()
+        .map(|line| line.split_whitespace().collect::<Vec<_>>())
+        .map(|line| {
+            let first = match line.first() {
+                Some(&"A") => Rock,
+                Some(&"B") => Paper,
+                Some(&"C") => Scissors,
+                _ => return Err(invalid(format!("Bad input {:?}", line))),
+            };
+            match line.get(1) {
+                Some(second @ (&"X" | &"Y" | &"Z")) => Ok((first, *second)),
+                _ => Err(invalid(format!("Bad input {:?}", line))),
+            }
+        })
+        .collect::<Result<_>>()?;
+
+    Ok(Rounds(rounds))
+}
+
+impl Rounds<'_> {
+    fn score(&self, mapping: &HashMap<&'static str, RPS>) -> i32 {
+        self.0
+            .iter()
+            .fold(0, |acc, (theirs, mine)| acc + mapping[mine].result(*theirs))
+    }
+
+    fn solve1(&self) -> (HashMap<&'static str, RPS>, i32) {
+        use RPS::*;
+        let mapping = HashMap::from([("X", Rock), ("Y", Paper), ("Z", Scissors)]);
+        let score = self.score(&mapping);
+        (mapping, score)
+    }
+
+    fn solve2(&self) -> (HashMap<&'static str, RPS>, i32) {
+        use RPS::*;
+        let mut mapping = HashMap::from([("X", Rock), ("Y", Paper), ("Z", Scissors)]);
+
+        let mut score = 0;
+        for round in &self.0 {
+            let mine = if round.1 == "X" {
+                round.0.lose()
+            } else if round.1 == "Y" {
+                round.0.draw()
+            } else if round.1 == "Z" {
+                round.0.win()
+            } else {
+                unreachable!("Can have this")
+            };
+            score += mine.result(round.0);
+            *mapping.get_mut(round.1).unwrap() = mine;
+        }
+
+        (mapping, score)
+    }
+
+    #[allow(dead_code)]
+    fn being_extra(&self) -> (HashMap<String, RPS>, i32) {
+        use RPS::*;
+        let candidates = [Rock, Paper, Scissors];
+        let permutations = (0..3).permutations(3);
+
+        // let mut mapping = HashMap::<&str, _>::new();
+        let scores = permutations
+            .map(|perm| {
+                let mapping = HashMap::from([
+                    ("X", candidates[perm[0]]),
+                    ("Y", candidates[perm[1]]),
+                    ("Z", candidates[perm[2]]),
+                ]);
+
+                (perm, self.score(&mapping))
+            })
+            .collect::<HashMap<_, _>>();
+
+        let best = scores
+            .iter()
+            .max_by(|(_, a), (_, b)| a.cmp(b))
+            .expect("No max?");
+
+        (
+            HashMap::from([
+                ("X".to_owned(), candidates[best.0[0]]),
+                ("Y".to_owned(), candidates[best.0[1]]),
+                ("Z".to_owned(), candidates[best.0[2]]),
+            ]),
+            *best.1,
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rock_paper_scissors() {
+        use RPS::*;
+        assert!(Rock > Scissors);
+        assert!(Scissors > Paper);
+        assert!(Paper > Rock);
+        assert!(Scissors < Rock);
+        assert!(Paper < Scissors);
+        assert!(Rock < Paper);
+        assert!(Rock == Rock);
+        assert!(Scissors == Scissors);
+        assert!(Paper == Paper);
+    }
+
+    #[test]
+    fn scores() {
+        use RPS::*;
+        assert_eq!(Paper.result(Rock), 8);
+        assert_eq!(Rock.result(Paper), 1);
+        assert_eq!(Scissors.result(Scissors), 6);
+    }
+}
@@ -0,0 +1,60 @@
+use anyhow::Result;
+use std::collections::{hash_map::RandomState, HashMap, HashSet};
+
+use crate::error::invalid;
+use crate::parse::map_lines;
+use crate::runner::Cancel;
+
+/// The rucksacks, one line of item letters each.
+pub fn parse(content: &str) -> Result<Vec<&str>> {
+    map_lines(content, |line| {
+        match line.chars().find(|c| !c.is_ascii_alphabetic()) {
+            Some(c) => Err(invalid(format!("unexpected item {:?}", c))),
+            None => Ok(line),
+        }
+    })
+}
+
+fn priority(item: char) -> u32 {
+    if item.is_ascii_uppercase() {
+        (item as u32) - ('A' as u32) + 27
+    } else {
+        (item as u32) - ('a' as u32) + 1
+    }
+}
+
+pub fn part1(rucksacks: &[&str], _cancel: &Cancel) -> Result<u32> {
+    rucksacks
+        .iter()
+        .map(|line| line.split_at(line.len() / 2))
+        .map(|(a, b)| {
+            a.chars()
+                .find(|a_char| b.find(*a_char).is_some())
+                .map(priority)
+                .ok_or_else(|| invalid(format!("No similarities in compartment: {:?}/{:?}", a, b)))
+        })
+        .sum()
+}
+
+pub fn part2(rucksacks: &[&str], _cancel: &Cancel) -> Result<u32> {
+    let mut map = HashMap::new();
+
+    rucksacks
+        .iter()
+        .map(|line| HashSet::<char, RandomState>::from_iter(line.chars()))
+        .collect::<Vec<_>>()
+        .chunks(3)
+        .map(|sets| {
+            map.clear();
+            sets.iter().for_each(|set| {
+                set.iter().for_each(|char| {
+                    map.entry(char).and_modify(|v| *v += 1).or_insert(1);
+                })
+            });
+            map.iter()
+                .find(|(_k, v)| **v == 3)
+                .map(|(k, _v)| priority(**k))
+                .ok_or_else(|| invalid(format!("No char occured 3 times in {:?}", sets)))
+        })
+        .sum()
+}
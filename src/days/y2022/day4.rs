@@ -0,0 +1,37 @@
+use anyhow::Result;
+use regex::Regex;
+
+use crate::parse::{captures, map_lines};
+use crate::runner::Cancel;
+
+pub type Range = (i32, i32);
+
+/// Parses "2-4,6-8" into its two ranges.
+pub fn parse(content: &str) -> Result<Vec<(Range, Range)>> {
+    let re = Regex::new(r"^(\d+)-(\d+),(\d+)-(\d+)$").unwrap();
+    map_lines(content, |line| {
+        let (a, b, c, d) = captures(&re, line)?;
+        Ok(((a, b), (c, d)))
+    })
+}
+
+pub fn part1(pairs: &[(Range, Range)], _cancel: &Cancel) -> Result<i32> {
+    let value = pairs
+        .iter()
+        .filter(|(first, second)| {
+            (first.0 <= second.0 && first.1 >= second.1)
+                || (second.0 <= first.0 && second.1 >= first.1)
+        })
+        .count();
+
+    Ok(value as i32)
+}
+
+pub fn part2(pairs: &[(Range, Range)], _cancel: &Cancel) -> Result<i32> {
+    let value = pairs
+        .iter()
+        .filter(|(first, second)| first.0 <= second.1 && second.0 <= first.1)
+        .count();
+
+    Ok(value as i32)
+}
@@ -0,0 +1,157 @@
+use anyhow::Result;
+use itertools::Itertools;
+use regex::Regex;
+
+use crate::error::invalid;
+use crate::parse::{blocks, captures, map_lines, uints};
+use crate::raster::Rgb;
+use crate::runner::Cancel;
+use crate::visualize::{Frame, Visualize};
+
+/// A move of `count` crates between two stacks, by index.
+pub type Move = (usize, usize, usize);
+
+/// The starting stacks, listed bottom first, and the moves to make.
+pub struct Crates {
+    stacks: Vec<Vec<char>>,
+    moves: Vec<Move>,
+}
+
+pub fn parse(content: &str) -> Result<Crates> {
+    let (drawing, moves) = blocks(content)
+        .collect_tuple()
+        .ok_or_else(|| invalid("expected a drawing and a list of moves"))?;
+
+    let mut lines = drawing.lines().rev();
+    let labels = uints::<usize>(lines.next().unwrap_or_default())?;
+    let mut rows = vec![Vec::new(); labels.len()];
+
+    for row in lines {
+        for (idx, letter) in row
+            .chars()
+            .chunks(4)
+            .into_iter()
+            .map(|mut chunk| chunk.nth(1))
+            .enumerate()
+        {
+            let letter = letter.ok_or_else(|| invalid(format!("Bad Char in {:?}", row)))?;
+            if !letter.is_whitespace() {
+                rows.get_mut(idx)
+                    .ok_or_else(|| invalid(format!("no stack under {:?} in {:?}", letter, row)))?
+                    .push(letter)
+            }
+        }
+    }
+
+    let stacks = rows.len();
+    let stack = |label: usize| {
+        label
+            .checked_sub(1)
+            .filter(|idx| *idx < stacks)
+            .ok_or_else(|| invalid(format!("no stack {}", label)))
+    };
+    let re = Regex::new(r"^move (\d+) from (\d+) to (\d+)$").unwrap();
+    let moves = map_lines(moves, |line| {
+        let (count, from, to) = captures(&re, line)?;
+        Ok((count, stack(from)?, stack(to)?))
+    })?;
+
+    Ok(Crates {
+        stacks: rows,
+        moves,
+    })
+}
+
+fn tops(rows: &[Vec<char>]) -> Result<String> {
+    rows.iter()
+        .map(|row| row.last().ok_or_else(|| invalid("a stack ends up empty")))
+        .collect()
+}
+
+pub fn part1(crates: &Crates, cancel: &Cancel) -> Result<String> {
+    let mut rows = crates.stacks.clone();
+
+    for &(count, from, to) in &crates.moves {
+        cancel.check()?;
+        for _i in 0..count {
+            let thing = rows[from]
+                .pop()
+                .ok_or_else(|| invalid(format!("nothing left in stack {}", from + 1)))?;
+            rows[to].push(thing);
+        }
+    }
+
+    tops(&rows)
+}
+
+pub fn part2(crates: &Crates, cancel: &Cancel) -> Result<String> {
+    let mut rows = crates.stacks.clone();
+
+    for &(count, from, to) in &crates.moves {
+        cancel.check()?;
+        let idx = rows[from]
+            .len()
+            .checked_sub(count)
+            .ok_or_else(|| invalid(format!("not {} crates in stack {}", count, from + 1)))?;
+        let moved = rows[from].split_off(idx);
+        rows[to].extend(moved);
+    }
+
+    tops(&rows)
+}
+
+/// Moves up to `count` crates one at a time, returning how many moved.
+fn crane_9000(rows: &mut [Vec<char>], count: usize, from: usize, to: usize) -> usize {
+    let count = count.min(rows[from].len());
+    for _ in 0..count {
+        let thing = rows[from].pop().unwrap();
+        rows[to].push(thing);
+    }
+    count
+}
+
+/// Draws the stacks as in the puzzle, with the top `moved` crates of stack
+/// `to` highlighted.
+fn draw_stacks(rows: &[Vec<char>], height: usize, moved: Option<(usize, usize)>) -> Frame {
+    let mut frame = Frame::new((4 * rows.len()).saturating_sub(1), height + 1);
+    for (idx, row) in rows.iter().enumerate() {
+        let fresh = match moved {
+            Some((to, count)) if to == idx => row.len() - count,
+            _ => row.len(),
+        };
+        for (level, letter) in row.iter().enumerate() {
+            let color = if level >= fresh {
+                Rgb::YELLOW
+            } else {
+                Rgb::GREEN
+            };
+            let y = height - 1 - level;
+            frame.text(4 * idx, y, &format!("[{}]", letter), Some(color));
+        }
+        frame.text(4 * idx + 1, height, &(idx + 1).to_string(), Some(Rgb::GRAY));
+    }
+    frame
+}
+
+/// One frame per move of the CrateMover 9000, as in part 1.
+impl Visualize for Crates {
+    fn frames(&self) -> Box<dyn Iterator<Item = Frame> + '_> {
+        let mut rows = self.stacks.clone();
+        let mut height = rows.iter().map(Vec::len).max().unwrap_or(0);
+        for &(count, from, to) in &self.moves {
+            crane_9000(&mut rows, count, from, to);
+            height = height.max(rows[to].len());
+        }
+
+        let mut rows = self.stacks.clone();
+        let mut start = draw_stacks(&rows, height, None);
+        start.caption = "start".to_owned();
+        let moves = self.moves.iter().map(move |&(count, from, to)| {
+            let moved = crane_9000(&mut rows, count, from, to);
+            let mut frame = draw_stacks(&rows, height, Some((to, moved)));
+            frame.caption = format!("move {} from {} to {}", count, from + 1, to + 1);
+            frame
+        });
+        Box::new(std::iter::once(start).chain(moves))
+    }
+}
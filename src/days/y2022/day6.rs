@@ -0,0 +1,35 @@
+use anyhow::Result;
+use std::collections::HashSet;
+
+use crate::error::invalid;
+use crate::runner::Cancel;
+
+/// The datastream, one letter per character.
+pub fn parse(content: &str) -> Result<&[u8]> {
+    let signal = content.trim_end();
+    match signal.bytes().find(|c| !c.is_ascii_lowercase()) {
+        Some(c) => Err(invalid(format!(
+            "unexpected {:?} in the datastream",
+            c as char
+        ))),
+        None => Ok(signal.as_bytes()),
+    }
+}
+
+/// How many characters are read by the end of the first `len` distinct ones.
+fn marker(signal: &[u8], len: usize) -> Result<usize> {
+    let idx = signal
+        .windows(len)
+        .position(|s| HashSet::<&u8>::from_iter(s.iter()).len() == len)
+        .ok_or_else(|| invalid("Couldn't find any"))?;
+
+    Ok(idx + len)
+}
+
+pub fn part1(signal: &[u8], _cancel: &Cancel) -> Result<usize> {
+    marker(signal, 4)
+}
+
+pub fn part2(signal: &[u8], _cancel: &Cancel) -> Result<usize> {
+    marker(signal, 14)
+}
@@ -0,0 +1,93 @@
+use anyhow::Result;
+use std::collections::{HashMap, HashSet};
+
+use crate::error::invalid;
+use crate::runner::Cancel;
+
+const DISK: usize = 70_000_000;
+const NEEDED: usize = 30_000_000;
+
+/// Every file in the transcript, keyed by its path from the root.
+pub struct Filesystem<'a> {
+    files: HashMap<Vec<&'a str>, usize>,
+}
+
+impl<'a> Filesystem<'a> {
+    /// The total size of every directory that holds files, keyed by its path
+    /// from the root. The root itself is the empty path.
+    fn sizes(&self, cancel: &Cancel) -> Result<HashMap<&[&'a str], usize>> {
+        let mut sizes = HashMap::new();
+
+        for (path, size) in &self.files {
+            cancel.check()?;
+            // Every directory above the file, from the root down.
+            for i in 0..path.len() {
+                *sizes.entry(&path[..i]).or_insert(0) += size;
+            }
+        }
+
+        Ok(sizes)
+    }
+}
+
+/// Replays the terminal transcript.
+pub fn parse(content: &str) -> Result<Filesystem<'_>> {
+    let mut dirs = HashSet::from([Vec::new()]);
+    let mut files = HashMap::new();
+    let mut current = Vec::new();
+
+    for line in content.lines() {
+        if let Some(goto) = line.strip_prefix("$ cd ") {
+            match goto {
+                "/" => current.clear(),
+                ".." => {
+                    current.pop();
+                }
+                next => {
+                    current.push(next);
+
+                    if !dirs.contains(&current) {
+                        return Err(invalid(format!("cd into unknown directory: {:?}", line)));
+                    }
+                }
+            }
+        } else if line == "$ ls" {
+        } else if let Some(name) = line.strip_prefix("dir ") {
+            let mut next = current.clone();
+            next.push(name);
+            dirs.insert(next);
+        } else if let Some((size, name)) = line.split_once(' ') {
+            let size = size
+                .parse::<usize>()
+                .map_err(|err| invalid(format!("{}: {:?}", err, line)))?;
+            let mut next = current.clone();
+            next.push(name);
+            if files.insert(next, size).is_some() {
+                return Err(invalid(format!("listed twice: {:?}", line)));
+            }
+        } else {
+            return Err(invalid(format!("unexpected line: {:?}", line)));
+        }
+    }
+
+    Ok(Filesystem { files })
+}
+
+pub fn part1(fs: &Filesystem, cancel: &Cancel) -> Result<usize> {
+    let sizes = fs.sizes(cancel)?;
+    Ok(sizes.values().filter(|size| **size <= 100_000).sum())
+}
+
+pub fn part2(fs: &Filesystem, cancel: &Cancel) -> Result<usize> {
+    let sizes = fs.sizes(cancel)?;
+    let used = sizes.get(&[][..]).copied().unwrap_or(0);
+    let free = DISK
+        .checked_sub(used)
+        .ok_or_else(|| invalid("filesystem is larger than the disk"))?;
+    sizes
+        .values()
+        .filter(|size| free + **size >= NEEDED)
+        .min()
+        .copied()
+        .ok_or_else(|| invalid("filesystem is too large to free enough space"))
+}
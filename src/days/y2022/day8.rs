@@ -0,0 +1,55 @@
+use anyhow::Result;
+
+use crate::geom::Direction;
+use crate::grid::Grid;
+use crate::runner::Cancel;
+
+pub fn parse(content: &str) -> Result<Grid<u32>> {
+    Grid::parse(content, |c| c.to_digit(10))
+}
+
+pub fn part1(grid: &Grid<u32>, cancel: &Cancel) -> Result<usize> {
+    let mut count: usize = 0;
+
+    for (pos, &height) in grid.iter() {
+        if pos.x == 0 {
+            cancel.check()?;
+        }
+        // Trees on the edge have an empty ray, so they're always visible.
+        let visible = Direction::ALL
+            .into_iter()
+            .any(|dir| grid.ray(pos, dir).all(|other| grid[other] < height));
+        if visible {
+            count += 1;
+        }
+    }
+
+    Ok(count)
+}
+
+pub fn part2(grid: &Grid<u32>, cancel: &Cancel) -> Result<usize> {
+    let mut best: usize = 0;
+
+    for (pos, &height) in grid.iter() {
+        if pos.x == 0 {
+            cancel.check()?;
+        }
+        let score = Direction::ALL
+            .into_iter()
+            .map(|dir| {
+                let mut seen = 0;
+                for other in grid.ray(pos, dir) {
+                    seen += 1;
+                    if grid[other] >= height {
+                        break;
+                    }
+                }
+                seen
+            })
+            .product::<usize>();
+
+        best = best.max(score);
+    }
+
+    Ok(best)
+}
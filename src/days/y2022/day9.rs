@@ -0,0 +1,139 @@
+use anyhow::Result;
+use std::collections::HashSet;
+
+use crate::error::invalid;
+use crate::geom::{Bounds, Direction, Point2};
+use crate::raster::Rgb;
+use crate::runner::Cancel;
+use crate::visualize::{Frame, Visualize};
+
+type Knot = Point2<isize>;
+
+pub fn part1(motions: &[(Direction, usize)], cancel: &Cancel) -> Result<usize> {
+    drag_rope::<2>(steps(motions), cancel)
+}
+
+pub fn part2(motions: &[(Direction, usize)], cancel: &Cancel) -> Result<usize> {
+    drag_rope::<10>(steps(motions), cancel)
+}
+
+/// Parses "R 4" into a direction and a count of 4.
+pub fn parse(input: &str) -> Result<Vec<(Direction, usize)>> {
+    input
+        .lines()
+        .map(|line| {
+            let (dir, count) = line
+                .split_once(' ')
+                .ok_or_else(|| invalid(format!("bad motion: {:?}", line)))?;
+            Ok((dir.parse()?, count.parse::<usize>()?))
+        })
+        .collect()
+}
+
+/// One single-square step per move of the head.
+pub(crate) fn steps(motions: &[(Direction, usize)]) -> impl Iterator<Item = Knot> + '_ {
+    motions
+        .iter()
+        .flat_map(|&(dir, count)| std::iter::repeat_n(dir.offset(), count))
+}
+
+/// Where `tail` ends up after `head` moves.
+pub(crate) fn update_tail(head: Knot, tail: Knot) -> Knot {
+    if head.chebyshev(tail) <= 1 {
+        return tail; //no need to move
+    }
+
+    // One step on each axis towards the head, diagonally if need be.
+    tail + (head - tail).signum()
+}
+
+fn drag_rope<const ROPE_LENGTH: usize>(
+    steps: impl Iterator<Item = Knot>,
+    cancel: &Cancel,
+) -> Result<usize> {
+    assert!(ROPE_LENGTH >= 2, "nontrivial rope");
+
+    let mut rope = [Knot::ZERO; ROPE_LENGTH];
+    let mut unique_tail_locations = HashSet::new();
+    unique_tail_locations.insert(rope[ROPE_LENGTH - 1]);
+
+    for step in steps {
+        cancel.check()?;
+        rope[0] += step;
+        for i in 1..ROPE_LENGTH {
+            rope[i] = update_tail(rope[i - 1], rope[i]);
+        }
+
+        unique_tail_locations.insert(rope[ROPE_LENGTH - 1]);
+    }
+
+    Ok(unique_tail_locations.len())
+}
+
+/// The largest view drawn; bigger ropes are followed by centering on the head.
+const VIEW: Point2<isize> = Point2 { x: 61, y: 31 };
+
+/// Rows grow downwards on screen, so "U" is towards row 0.
+fn draw_rope(rope: &[Knot], visited: &HashSet<Knot>, view: Bounds<isize>) -> Frame {
+    let mut frame = Frame::new(view.width() as usize, view.height() as usize);
+    let mut put = |knot: Knot, ch: char, color: Rgb| {
+        if view.contains(knot) {
+            let at = knot - view.min;
+            frame.put(at.x as usize, at.y as usize, ch, Some(color));
+        }
+    };
+    for &spot in visited {
+        put(spot, '#', Rgb::GRAY);
+    }
+    put(Knot::ZERO, 's', Rgb::BLUE);
+    // Drawn tail first so knots nearer the head stay on top.
+    for (idx, &knot) in rope.iter().enumerate().rev() {
+        match idx {
+            0 => put(knot, 'H', Rgb::RED),
+            _ => put(
+                knot,
+                char::from_digit(idx as u32, 10).unwrap_or('T'),
+                Rgb::YELLOW,
+            ),
+        }
+    }
+    frame
+}
+
+/// One frame per motion of a ten-knot rope, as in part 2, with the cells the
+/// tail has visited marked.
+impl Visualize for [(Direction, usize)] {
+    fn frames(&self) -> Box<dyn Iterator<Item = Frame> + '_> {
+        let mut rope = [Knot::ZERO; 10];
+        let mut bounds = Bounds::new(Knot::ZERO, Knot::ZERO);
+        for step in steps(self) {
+            rope[0] += step;
+            bounds.extend(rope[0]);
+        }
+        let fixed = (bounds.width() <= VIEW.x && bounds.height() <= VIEW.y).then_some(bounds);
+        let view = move |head: Knot| {
+            fixed.unwrap_or_else(|| {
+                let corner = head - VIEW / 2;
+                Bounds::new(corner, corner + VIEW - Point2::new(1, 1))
+            })
+        };
+
+        let mut rope = [Knot::ZERO; 10];
+        let mut visited = HashSet::from([Knot::ZERO]);
+        let mut start = draw_rope(&rope, &visited, view(rope[0]));
+        start.caption = "start".to_owned();
+        let motions = self.iter().map(move |&(dir, count)| {
+            for _ in 0..count {
+                rope[0] += dir.offset();
+                for i in 1..rope.len() {
+                    rope[i] = update_tail(rope[i - 1], rope[i]);
+                }
+                visited.insert(rope[rope.len() - 1]);
+            }
+            let mut frame = draw_rope(&rope, &visited, view(rope[0]));
+            frame.caption = format!("{:?} {} ({} visited)", dir, count, visited.len());
+            frame
+        });
+        Box::new(std::iter::once(start).chain(motions))
+    }
+}
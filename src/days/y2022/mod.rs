@@ -0,0 +1,122 @@
+//! Advent of Code 2022.
+
+use super::{Backend, DayInfo};
+use crate::lint;
+
+pub mod day1;
+pub mod day2;
+pub mod day3;
+pub mod day4;
+pub mod day5;
+pub mod day6;
+pub mod day7;
+pub mod day8;
+pub mod day9;
+
+/// The compute backend only exists in builds with the `gpu` feature.
+#[cfg(feature = "gpu")]
+const DAY1_BACKENDS: &[Backend] = &[Backend::Cpu, Backend::Compute];
+#[cfg(not(feature = "gpu"))]
+const DAY1_BACKENDS: &[Backend] = &[Backend::Cpu];
+
+pub const DAYS: &[DayInfo] = &[
+    DayInfo {
+        day: 1,
+        title: "Calorie Counting",
+        parse: parser!(day1),
+        backends: DAY1_BACKENDS,
+        part1: solver!(day1::part1),
+        part2: solver!(day1::part2),
+        both: both!(day1),
+        lint: Some(lint::y2022::day1),
+        visualize: None,
+    },
+    DayInfo {
+        day: 2,
+        title: "Rock Paper Scissors",
+        parse: parser!(day2),
+        backends: &[Backend::Cpu],
+        part1: solver!(day2::part1),
+        part2: solver!(day2::part2),
+        both: both!(day2),
+        lint: Some(lint::y2022::day2),
+        visualize: None,
+    },
+    DayInfo {
+        day: 3,
+        title: "Rucksack Reorganization",
+        parse: parser!(day3),
+        backends: &[Backend::Cpu],
+        part1: solver!(day3::part1),
+        part2: solver!(day3::part2),
+        both: both!(day3),
+        lint: Some(lint::y2022::day3),
+        visualize: None,
+    },
+    DayInfo {
+        day: 4,
+        title: "Camp Cleanup",
+        parse: parser!(day4),
+        backends: &[Backend::Cpu],
+        part1: solver!(day4::part1),
+        part2: solver!(day4::part2),
+        both: both!(day4),
+        lint: Some(lint::y2022::day4),
+        visualize: None,
+    },
+    DayInfo {
+        day: 5,
+        title: "Supply Stacks",
+        parse: parser!(day5),
+        backends: &[Backend::Cpu],
+        part1: solver!(day5::part1),
+        part2: solver!(day5::part2),
+        both: both!(day5),
+        lint: Some(lint::y2022::day5),
+        visualize: visualizer!(day5),
+    },
+    DayInfo {
+        day: 6,
+        title: "Tuning Trouble",
+        parse: parser!(day6),
+        backends: &[Backend::Cpu],
+        part1: solver!(day6::part1),
+        part2: solver!(day6::part2),
+        both: both!(day6),
+        lint: Some(lint::y2022::day6),
+        visualize: None,
+    },
+    DayInfo {
+        day: 7,
+        title: "No Space Left On Device",
+        parse: parser!(day7),
+        backends: &[Backend::Cpu],
+        part1: solver!(day7::part1),
+        part2: solver!(day7::part2),
+        both: both!(day7),
+        lint: Some(lint::y2022::day7),
+        visualize: None,
+    },
+    DayInfo {
+        day: 8,
+        title: "Treetop Tree House",
+        parse: parser!(day8),
+        backends: &[Backend::Cpu],
+        part1: solver!(day8::part1),
+        part2: solver!(day8::part2),
+        both: both!(day8),
+        lint: Some(lint::y2022::day8),
+        visualize: None,
+    },
+    DayInfo {
+        day: 9,
+        title: "Rope Bridge",
+        parse: parser!(day9),
+        backends: &[Backend::Cpu],
+        part1: solver!(day9::part1),
+        part2: solver!(day9::part2),
+        both: both!(day9),
+        lint: Some(lint::y2022::day9),
+        visualize: visualizer!(day9),
+    },
+];
@@ -0,0 +1,405 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result};
+
+pub const DEFAULT_BASE_URL: &str = "https://adventofcode.com";
+
+const USER_AGENT: &str = "github.com/Dacode45/advent-of-code-2022 (advent describe)";
+
+/// The only host `AOC_SESSION` is ever sent to.
+const SESSION_HOST: &str = "adventofcode.com";
+
+/// Downloads the puzzle page for `day` of `year`. Part two is only on the
+/// page when `AOC_SESSION` holds a logged in session cookie, which is only
+/// sent over HTTPS to adventofcode.com itself, whatever `base_url` says.
+pub fn fetch(base_url: &str, year: u16, day: u8) -> Result<String> {
+    let url = format!("{}/{}/day/{}", base_url.trim_end_matches('/'), year, day);
+    let mut request = ureq::get(&url).set("User-Agent", USER_AGENT);
+    if let Ok(session) = std::env::var("AOC_SESSION") {
+        if request.request_url().is_ok_and(|url| takes_session(&url)) {
+            request = request.set("Cookie", &format!("session={}", session));
+        }
+    }
+    let page = request
+        .call()
+        .with_context(|| format!("fetching {}", url))?
+        .into_string()?;
+    Ok(page)
+}
+
+fn takes_session(url: &ureq::RequestUrl) -> bool {
+    url.scheme() == "https" && url.host() == SESSION_HOST
+}
+
+/// Returns the puzzle page for `day` of `year`, fetching it only if
+/// `cache_dir` doesn't have a copy yet.
+pub fn cached(cache_dir: &Path, base_url: &str, year: u16, day: u8) -> Result<String> {
+    let dir = cache_dir.join(year.to_string());
+    let path = dir.join(format!("day{}.html", day));
+    if path.exists() {
+        return Ok(fs::read_to_string(&path)?);
+    }
+
+    let page = fetch(base_url, year, day)?;
+    fs::create_dir_all(&dir)?;
+    fs::write(&path, &page)?;
+    Ok(page)
+}
+
+/// The inner HTML of every `<article>` on the page, part one first.
+pub fn articles(page: &str) -> Vec<&str> {
+    let mut found = Vec::new();
+    let mut rest = page;
+    while let Some(start) = rest.find("<article") {
+        let open_end = match rest[start..].find('>') {
+            Some(idx) => start + idx + 1,
+            None => break,
+        };
+        let close = match rest[open_end..].find("</article>") {
+            Some(idx) => open_end + idx,
+            None => break,
+        };
+        found.push(&rest[open_end..close]);
+        rest = &rest[close + "</article>".len()..];
+    }
+    found
+}
+
+#[derive(Debug, PartialEq, Eq)]
+enum Token<'a> {
+    Open(&'a str, &'a str),
+    Close(&'a str),
+    Text(&'a str),
+}
+
+fn tokens(html: &str) -> Vec<Token<'_>> {
+    let mut tokens = Vec::new();
+    let mut rest = html;
+    while !rest.is_empty() {
+        let lt = rest.find('<').unwrap_or(rest.len());
+        if lt > 0 {
+            tokens.push(Token::Text(&rest[..lt]));
+            rest = &rest[lt..];
+            continue;
+        }
+        let gt = match rest.find('>') {
+            Some(gt) => gt,
+            None => {
+                tokens.push(Token::Text(rest));
+                break;
+            }
+        };
+        let tag = rest[1..gt].trim_end_matches('/').trim();
+        if let Some(name) = tag.strip_prefix('/') {
+            tokens.push(Token::Close(name.trim()));
+        } else if !tag.starts_with('!') {
+            let (name, attrs) = tag.split_once(char::is_whitespace).unwrap_or((tag, ""));
+            tokens.push(Token::Open(name, attrs));
+        }
+        rest = &rest[gt + 1..];
+    }
+    tokens
+}
+
+fn attr<'a>(attrs: &'a str, name: &str) -> Option<&'a str> {
+    let start = attrs.find(&format!("{}=\"", name))? + name.len() + 2;
+    let len = attrs[start..].find('"')?;
+    Some(&attrs[start..start + len])
+}
+
+fn decode(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(amp) = rest.find('&') {
+        out.push_str(&rest[..amp]);
+        rest = &rest[amp..];
+        let semi = match rest.find(';') {
+            Some(semi) if semi <= 10 => semi,
+            _ => {
+                out.push('&');
+                rest = &rest[1..];
+                continue;
+            }
+        };
+        let decoded = match &rest[1..semi] {
+            "lt" => Some('<'),
+            "gt" => Some('>'),
+            "amp" => Some('&'),
+            "quot" => Some('"'),
+            "apos" => Some('\''),
+            "nbsp" => Some(' '),
+            entity => entity
+                .strip_prefix("#x")
+                .and_then(|hex| u32::from_str_radix(hex, 16).ok())
+                .or_else(|| entity.strip_prefix('#').and_then(|dec| dec.parse().ok()))
+                .and_then(char::from_u32),
+        };
+        match decoded {
+            Some(c) => {
+                out.push(c);
+                rest = &rest[semi + 1..];
+            }
+            None => {
+                out.push('&');
+                rest = &rest[1..];
+            }
+        }
+    }
+    out.push_str(rest);
+    out
+}
+
+/// Renders one puzzle article as Markdown for reading in a terminal.
+pub fn to_markdown(article: &str) -> String {
+    let mut out = String::new();
+    let mut in_pre = false;
+    let mut links = Vec::new();
+
+    for token in tokens(article) {
+        match token {
+            Token::Text(text) if in_pre => out.push_str(&decode(text)),
+            Token::Text(text) => {
+                let text = decode(text);
+                let words = text.split_whitespace().collect::<Vec<_>>().join(" ");
+                let at_break = out.is_empty() || out.ends_with([' ', '\n']);
+                if text.starts_with(char::is_whitespace) && !at_break {
+                    out.push(' ');
+                }
+                out.push_str(&words);
+                if text.ends_with(char::is_whitespace) && !words.is_empty() {
+                    out.push(' ');
+                }
+            }
+            Token::Open(name, attrs) => match name {
+                "h2" => out.push_str("## "),
+                "pre" => {
+                    in_pre = true;
+                    out.push_str("```\n");
+                }
+                "code" | "em" if in_pre => {}
+                "code" => out.push('`'),
+                "em" => out.push_str("**"),
+                "a" => {
+                    links.push(attr(attrs, "href").unwrap_or_default().to_owned());
+                    out.push('[');
+                }
+                "li" => out.push_str("- "),
+                "br" => out.push('\n'),
+                _ => {}
+            },
+            Token::Close(name) => match name {
+                "h2" | "p" | "ul" => out.push_str("\n\n"),
+                "pre" => {
+                    in_pre = false;
+                    if !out.ends_with('\n') {
+                        out.push('\n');
+                    }
+                    out.push_str("```\n\n");
+                }
+                "code" | "em" if in_pre => {}
+                "code" => out.push('`'),
+                "em" => out.push_str("**"),
+                "a" => {
+                    let href = links.pop().unwrap_or_default();
+                    out.push_str(&format!("]({})", href));
+                }
+                "li" => out.push('\n'),
+                _ => {}
+            },
+        }
+    }
+
+    let mut markdown = out
+        .lines()
+        .map(str::trim_end)
+        .collect::<Vec<_>>()
+        .join("\n");
+    while markdown.contains("\n\n\n") {
+        markdown = markdown.replace("\n\n\n", "\n\n");
+    }
+    markdown.trim().to_owned() + "\n"
+}
+
+/// The plain text of the first `<pre><code>` block in `article`, which is
+/// where the puzzle pages keep their example input.
+pub fn example(article: &str) -> Option<String> {
+    let mut text = String::new();
+    let mut in_pre = false;
+    for token in tokens(article) {
+        match token {
+            Token::Open("pre", _) => in_pre = true,
+            Token::Close("pre") if in_pre => return Some(text),
+            Token::Text(chunk) if in_pre => text.push_str(&decode(chunk)),
+            _ => {}
+        }
+    }
+    None
+}
+
+/// Writes each article's example to `inputs_dir`, as `dayN_example.txt` for
+/// part one and `dayN_example_2.txt` for a different part two example.
+/// Existing files are left alone. Returns the files written.
+pub fn save_examples(inputs_dir: &Path, day: u8, articles: &[&str]) -> Result<Vec<PathBuf>> {
+    fs::create_dir_all(inputs_dir)?;
+    let mut written = Vec::new();
+    let mut previous = None;
+    for (idx, article) in articles.iter().enumerate() {
+        let example = match example(article) {
+            Some(example) => example,
+            None => continue,
+        };
+        if previous.as_ref() == Some(&example) {
+            continue;
+        }
+        let name = if idx == 0 {
+            format!("day{}_example.txt", day)
+        } else {
+            format!("day{}_example_{}.txt", day, idx + 1)
+        };
+        let path = inputs_dir.join(name);
+        if !path.exists() {
+            fs::write(&path, &example)?;
+            written.push(path);
+        }
+        previous = Some(example);
+    }
+    Ok(written)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::{BufRead, BufReader, Write};
+    use std::net::TcpListener;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+
+    const PAGE: &str = r#"<!DOCTYPE html>
+<html><body><main>
+<article class="day-desc"><h2>--- Day 1: Calorie Counting ---</h2><p>The Elves take turns writing down the number of <em>Calories</em> contained by the various meals &amp; snacks.</p>
+<p>For example, suppose the Elves finished writing their items' Calories and ended up with the following list:</p>
+<pre><code>1000
+2000
+
+<em>4000</em>
+</code></pre>
+<ul><li>The first Elf is carrying <code>3000</code> Calories.</li></ul>
+<p>Find the Elf carrying the most Calories. See <a href="/2022/about">about</a>.</p>
+</article>
+<article class="day-desc"><h2 id="part2">--- Part Two ---</h2><p>Top three &lt;3</p>
+<pre><code>1000
+2000
+
+4000
+</code></pre></article>
+</main></body></html>"#;
+
+    /// Serves `PAGE` as 2022 day 1, counting requests and the cookies they
+    /// carry.
+    fn stand_in() -> (String, Arc<AtomicUsize>, Arc<AtomicUsize>) {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let base = format!("http://{}", listener.local_addr().unwrap());
+        let hits = Arc::new(AtomicUsize::new(0));
+        let cookies = Arc::new(AtomicUsize::new(0));
+        let (counter, cookie_counter) = (hits.clone(), cookies.clone());
+        std::thread::spawn(move || {
+            for stream in listener.incoming() {
+                let mut stream = stream.unwrap();
+                let mut reader = BufReader::new(&stream);
+                let mut request = String::new();
+                reader.read_line(&mut request).unwrap();
+                loop {
+                    let mut header = String::new();
+                    if reader.read_line(&mut header).unwrap() == 0 || header.trim().is_empty() {
+                        break;
+                    }
+                    if header.to_ascii_lowercase().starts_with("cookie:") {
+                        cookie_counter.fetch_add(1, Ordering::SeqCst);
+                    }
+                }
+                counter.fetch_add(1, Ordering::SeqCst);
+                let (status, body) = if request.starts_with("GET /2022/day/1 ") {
+                    ("200 OK", PAGE)
+                } else {
+                    ("404 Not Found", "")
+                };
+                write!(
+                    stream,
+                    "HTTP/1.1 {}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                    status,
+                    body.len(),
+                    body
+                )
+                .unwrap();
+            }
+        });
+        (base, hits, cookies)
+    }
+
+    #[test]
+    fn fetches_once() {
+        let (base, hits, _) = stand_in();
+        let dir = std::env::temp_dir().join(format!("advent-describe-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+
+        assert_eq!(cached(&dir, &base, 2022, 1).unwrap(), PAGE);
+        assert_eq!(cached(&dir, &base, 2022, 1).unwrap(), PAGE);
+        assert_eq!(hits.load(Ordering::SeqCst), 1);
+        assert!(dir.join("2022/day1.html").exists());
+        assert!(cached(&dir, &base, 2022, 2).is_err());
+        assert!(cached(&dir, &base, 2021, 1).is_err());
+
+        let page = cached(&dir, &base, 2022, 1).unwrap();
+        let written = save_examples(&dir, 1, &articles(&page)).unwrap();
+        assert_eq!(written, vec![dir.join("day1_example.txt")]);
+        assert_eq!(
+            fs::read_to_string(&written[0]).unwrap(),
+            "1000\n2000\n\n4000\n"
+        );
+        assert!(save_examples(&dir, 1, &articles(&page)).unwrap().is_empty());
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn keeps_the_session_to_itself() {
+        std::env::set_var("AOC_SESSION", "53cr37");
+        let (base, hits, cookies) = stand_in();
+        assert_eq!(fetch(&base, 2022, 1).unwrap(), PAGE);
+        assert_eq!(hits.load(Ordering::SeqCst), 1);
+        assert_eq!(cookies.load(Ordering::SeqCst), 0);
+
+        let takes = |url: &str| takes_session(&ureq::get(url).request_url().unwrap());
+        assert!(takes("https://adventofcode.com/2022/day/1"));
+        assert!(takes("https://adventofcode.com:443/2022/day/1"));
+        for url in [
+            "http://adventofcode.com/2022/day/1",
+            "https://adventofcode.com.example.net/2022/day/1",
+            "https://adventofcode.com@example.net/2022/day/1",
+            "https://example.net/adventofcode.com/day/1",
+            "https://adventofcodes.com/2022/day/1",
+        ] {
+            assert!(!takes(url), "{}", url);
+        }
+    }
+
+    #[test]
+    fn renders_markdown() {
+        let found = articles(PAGE);
+        assert_eq!(found.len(), 2);
+        assert_eq!(
+            to_markdown(found[0]),
+            "## --- Day 1: Calorie Counting ---\n\n\
+             The Elves take turns writing down the number of **Calories** contained by the various meals & snacks.\n\n\
+             For example, suppose the Elves finished writing their items' Calories and ended up with the following list:\n\n\
+             ```\n1000\n2000\n\n4000\n```\n\n\
+             - The first Elf is carrying `3000` Calories.\n\n\
+             Find the Elf carrying the most Calories. See [about](/2022/about).\n"
+        );
+        assert_eq!(
+            to_markdown(found[1]),
+            "## --- Part Two ---\n\nTop three <3\n\n```\n1000\n2000\n\n4000\n```\n"
+        );
+    }
+}
@@ -0,0 +1,117 @@
+use std::fmt::Display;
+use std::io;
+use std::num::{ParseFloatError, ParseIntError};
+use std::path::{Path, PathBuf};
+
+use thiserror::Error;
+
+use crate::days::Unsupported;
+use crate::runner::TimedOut;
+
+/// Why a run failed. Each category exits with its own code so scripts can
+/// tell a bad input file apart from a solver bug.
+#[derive(Debug, Error)]
+pub enum Error {
+    #[error("can't read {}: {error}", .path.display())]
+    Io { path: PathBuf, error: io::Error },
+    #[error("invalid input: {0:#}")]
+    Parse(anyhow::Error),
+    #[error("solver failed: {0:#}")]
+    Solve(anyhow::Error),
+    #[error(transparent)]
+    Unsupported(#[from] Unsupported),
+    /// A tool has nothing for the day asked about, such as lint rules or an
+    /// animation.
+    #[error("{0:#}")]
+    Unavailable(anyhow::Error),
+    #[error("can't write output: {0:#}")]
+    Output(anyhow::Error),
+    #[error("network error: {0:#}")]
+    Network(anyhow::Error),
+    #[error(transparent)]
+    TimedOut(#[from] TimedOut),
+}
+
+impl Error {
+    /// Sorts an error returned by a solver into input and logic failures.
+    pub fn from_solver(err: anyhow::Error) -> Self {
+        if let Some(timed_out) = err.downcast_ref::<TimedOut>() {
+            return Error::TimedOut(*timed_out);
+        }
+        let bad_input = err.chain().any(|cause| {
+            cause.is::<ParseError>() || cause.is::<ParseIntError>() || cause.is::<ParseFloatError>()
+        });
+        if bad_input {
+            Error::Parse(err)
+        } else {
+            Error::Solve(err)
+        }
+    }
+
+    pub fn exit_code(&self) -> i32 {
+        match self {
+            Error::Solve(_) => 1,
+            Error::Unsupported(_) | Error::Unavailable(_) => 2,
+            Error::Io { .. } | Error::Output(_) => 3,
+            Error::Parse(_) => 4,
+            Error::Network(_) => 5,
+            // Matches timeout(1).
+            Error::TimedOut(_) => 124,
+        }
+    }
+}
+
+/// Reads a whole input file, keeping its path for the error message.
+pub fn read_input(path: &Path) -> Result<String, Error> {
+    std::fs::read_to_string(path).map_err(|error| Error::Io {
+        path: path.to_owned(),
+        error,
+    })
+}
+
+/// The input doesn't have the shape the puzzle promises.
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+#[error("{0}")]
+pub struct ParseError(pub String);
+
+/// Shorthand for failing a solver on malformed input.
+pub fn invalid(message: impl Display) -> anyhow::Error {
+    ParseError(message.to_string()).into()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use anyhow::Context;
+    use std::time::Duration;
+
+    #[test]
+    fn categories() {
+        let parse = Error::from_solver(invalid("bad line"));
+        assert_eq!(parse.exit_code(), 4);
+        assert_eq!(parse.to_string(), "invalid input: bad line");
+
+        let nested = "x".parse::<i32>().context("line 3").unwrap_err();
+        assert!(matches!(Error::from_solver(nested), Error::Parse(_)));
+
+        let solve = Error::from_solver(anyhow::anyhow!("solver panicked"));
+        assert_eq!(solve.exit_code(), 1);
+        assert_eq!(solve.to_string(), "solver failed: solver panicked");
+
+        let timed_out = Error::from_solver(TimedOut(Duration::from_secs(1)).into());
+        assert_eq!(timed_out.exit_code(), 124);
+
+        let codes = [
+            Error::Unavailable(anyhow::anyhow!("no lint rules")).exit_code(),
+            Error::Output(anyhow::anyhow!("disk full")).exit_code(),
+            Error::Network(anyhow::anyhow!("offline")).exit_code(),
+        ];
+        assert_eq!(codes, [2, 3, 5]);
+
+        let io = read_input(Path::new("inputs/missing.txt")).unwrap_err();
+        assert_eq!(io.exit_code(), 3);
+        assert!(io
+            .to_string()
+            .starts_with("can't read inputs/missing.txt: "));
+    }
+}
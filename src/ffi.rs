@@ -0,0 +1,208 @@
+//! C ABI over the solver registry. The matching header
+//! lives in `include/advent.h` and is regenerated by `tests/ffi.rs`.
+
+use std::panic;
+use std::slice;
+
+use crate::days;
+use crate::runner::Cancel;
+
+/// Status codes returned by [`advent_solve`].
+#[repr(i32)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AdventStatus {
+    Ok = 0,
+    NullPointer = 1,
+    InvalidUtf8 = 2,
+    UnknownSolver = 3,
+    SolveFailed = 4,
+    BufferTooSmall = 5,
+    Panicked = 6,
+}
+
+/// Solves `part` of `day` of the latest year for the `input_len` bytes of
+/// UTF-8 at `input_ptr`.
+///
+/// `*out_len` holds the capacity of `out_buf` on entry. On success the answer
+/// is written to `out_buf` NUL-terminated and `*out_len` is set to its length
+/// without the NUL. `SolveFailed` writes the error message the same way,
+/// truncated to fit. `BufferTooSmall` leaves `out_buf` untouched and sets
+/// `*out_len` to the capacity the answer needs, NUL included.
+///
+/// # Safety
+///
+/// `input_ptr` must point to `input_len` readable bytes, `out_buf` to
+/// `*out_len` writable bytes, and `out_len` must be valid for reads and
+/// writes.
+#[no_mangle]
+pub unsafe extern "C" fn advent_solve(
+    day: u32,
+    part: u32,
+    input_ptr: *const u8,
+    input_len: usize,
+    out_buf: *mut u8,
+    out_len: *mut usize,
+) -> AdventStatus {
+    advent_solve_year(
+        days::LATEST_YEAR.into(),
+        day,
+        part,
+        input_ptr,
+        input_len,
+        out_buf,
+        out_len,
+    )
+}
+
+/// [`advent_solve`] for `day` of `year`, which fails with `UnknownSolver`
+/// unless that year is solved.
+///
+/// # Safety
+///
+/// As for [`advent_solve`].
+#[no_mangle]
+pub unsafe extern "C" fn advent_solve_year(
+    year: u32,
+    day: u32,
+    part: u32,
+    input_ptr: *const u8,
+    input_len: usize,
+    out_buf: *mut u8,
+    out_len: *mut usize,
+) -> AdventStatus {
+    if (input_ptr.is_null() && input_len != 0) || out_buf.is_null() || out_len.is_null() {
+        return AdventStatus::NullPointer;
+    }
+
+    let input = if input_len == 0 {
+        &[][..]
+    } else {
+        slice::from_raw_parts(input_ptr, input_len)
+    };
+    let input = match std::str::from_utf8(input) {
+        Ok(input) => input,
+        Err(_) => return AdventStatus::InvalidUtf8,
+    };
+
+    let solve = match (
+        u16::try_from(year).ok(),
+        u8::try_from(day).ok(),
+        u8::try_from(part).ok(),
+    ) {
+        (Some(year), Some(day), Some(part)) => days::solver(year, day, part),
+        _ => None,
+    };
+    let solve = match solve {
+        Some(solve) => solve,
+        None => return AdventStatus::UnknownSolver,
+    };
+
+    let capacity = *out_len;
+    let out = slice::from_raw_parts_mut(out_buf, capacity);
+
+    match panic::catch_unwind(|| solve(input, &Cancel::new())) {
+        Ok(Ok(answer)) => {
+            if answer.len() >= capacity {
+                *out_len = answer.len() + 1;
+                return AdventStatus::BufferTooSmall;
+            }
+            *out_len = write_c_str(out, &answer);
+            AdventStatus::Ok
+        }
+        Ok(Err(err)) => {
+            *out_len = write_c_str(out, &format!("{:#}", err));
+            AdventStatus::SolveFailed
+        }
+        Err(_) => AdventStatus::Panicked,
+    }
+}
+
+/// Copies as much of `text` as fits in `out` followed by a NUL, returning the
+/// number of text bytes written.
+fn write_c_str(out: &mut [u8], text: &str) -> usize {
+    if out.is_empty() {
+        return 0;
+    }
+    let mut len = text.len().min(out.len() - 1);
+    while !text.is_char_boundary(len) {
+        len -= 1;
+    }
+    out[..len].copy_from_slice(&text.as_bytes()[..len]);
+    out[len] = 0;
+    len
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn solve(day: u32, part: u32, input: &str, capacity: usize) -> (AdventStatus, String, usize) {
+        call(capacity, |out, len| unsafe {
+            advent_solve(day, part, input.as_ptr(), input.len(), out, len)
+        })
+    }
+
+    fn solve_year(
+        year: u32,
+        day: u32,
+        part: u32,
+        input: &str,
+        capacity: usize,
+    ) -> (AdventStatus, String, usize) {
+        call(capacity, |out, len| unsafe {
+            advent_solve_year(year, day, part, input.as_ptr(), input.len(), out, len)
+        })
+    }
+
+    /// Runs `solve` on a `capacity`-byte output buffer, returning its status,
+    /// the text written and the final `*out_len`.
+    fn call(
+        capacity: usize,
+        solve: impl FnOnce(*mut u8, *mut usize) -> AdventStatus,
+    ) -> (AdventStatus, String, usize) {
+        let mut out = vec![0xffu8; capacity];
+        let mut len = capacity;
+        let status = solve(out.as_mut_ptr(), &mut len);
+        let text = out
+            .iter()
+            .take_while(|b| **b != 0)
+            .map(|b| *b as char)
+            .collect();
+        (status, text, len)
+    }
+
+    #[test]
+    fn answers_and_errors() {
+        let input = include_str!("../inputs/2022/day5_example.txt");
+        assert_eq!(
+            solve(5, 1, input, 64),
+            (AdventStatus::Ok, "CMZ".to_owned(), 3)
+        );
+        assert_eq!(solve(5, 1, input, 3).0, AdventStatus::BufferTooSmall);
+        assert_eq!(solve(5, 1, input, 3).2, 4);
+        assert_eq!(solve(5, 3, input, 64).0, AdventStatus::UnknownSolver);
+        assert_eq!(solve(300, 1, input, 64).0, AdventStatus::UnknownSolver);
+        assert_eq!(solve_year(2022, 5, 2, input, 64).1, "MCD");
+        assert_eq!(
+            solve_year(2015, 5, 1, input, 64).0,
+            AdventStatus::UnknownSolver
+        );
+        assert_eq!(
+            solve_year(70000, 5, 1, input, 64).0,
+            AdventStatus::UnknownSolver
+        );
+
+        let (status, message, len) = solve(1, 1, "12\nx\n", 8);
+        assert_eq!(status, AdventStatus::SolveFailed);
+        assert_eq!(message, "invalid");
+        assert_eq!(len, 7);
+    }
+
+    #[test]
+    fn rejects_null_pointers() {
+        let mut len = 0;
+        let status =
+            unsafe { advent_solve(1, 1, std::ptr::null(), 4, std::ptr::null_mut(), &mut len) };
+        assert_eq!(status, AdventStatus::NullPointer);
+    }
+}
@@ -0,0 +1,547 @@
+//! Points, directions and bounding boxes.
+//!
+//! Directions use screen coordinates, the same as [`crate::grid`]: `x` grows
+//! to the right and `y` grows downwards, so [`Direction::Up`] is `(0, -1)`.
+
+use std::fmt;
+use std::ops::{Add, AddAssign, Div, DivAssign, Mul, MulAssign, Neg, Sub, SubAssign};
+use std::str::FromStr;
+
+use crate::error::invalid;
+
+/// Numbers usable as point coordinates.
+pub trait Coord:
+    Copy
+    + PartialOrd
+    + Add<Output = Self>
+    + Sub<Output = Self>
+    + Mul<Output = Self>
+    + Div<Output = Self>
+{
+    const ZERO: Self;
+    const ONE: Self;
+
+    /// How far apart two coordinates are: the unsigned type of the same width
+    /// for integers, which holds any difference without overflowing.
+    type Distance: Coord;
+
+    fn abs_diff(self, other: Self) -> Self::Distance;
+    /// -1, 0 or 1 by sign, always 0 or 1 for unsigned types.
+    fn signum(self) -> Self;
+    fn to_f64(self) -> f64;
+
+    fn max(self, other: Self) -> Self {
+        if other > self {
+            other
+        } else {
+            self
+        }
+    }
+
+    fn min(self, other: Self) -> Self {
+        if other < self {
+            other
+        } else {
+            self
+        }
+    }
+}
+
+macro_rules! signed_coord {
+    ($($ty:ty => $unsigned:ty),*) => {$(
+        impl Coord for $ty {
+            const ZERO: Self = 0;
+            const ONE: Self = 1;
+            type Distance = $unsigned;
+            fn abs_diff(self, other: Self) -> $unsigned {
+                <$ty>::abs_diff(self, other)
+            }
+            fn signum(self) -> Self {
+                <$ty>::signum(self)
+            }
+            fn to_f64(self) -> f64 {
+                self as f64
+            }
+        }
+    )*};
+}
+
+macro_rules! unsigned_coord {
+    ($($ty:ty),*) => {$(
+        impl Coord for $ty {
+            const ZERO: Self = 0;
+            const ONE: Self = 1;
+            type Distance = Self;
+            fn abs_diff(self, other: Self) -> Self {
+                <$ty>::abs_diff(self, other)
+            }
+            fn signum(self) -> Self {
+                (self > 0) as $ty
+            }
+            fn to_f64(self) -> f64 {
+                self as f64
+            }
+        }
+    )*};
+}
+
+macro_rules! float_coord {
+    ($($ty:ty),*) => {$(
+        impl Coord for $ty {
+            const ZERO: Self = 0.0;
+            const ONE: Self = 1.0;
+            type Distance = Self;
+            fn abs_diff(self, other: Self) -> Self {
+                (self - other).abs()
+            }
+            fn signum(self) -> Self {
+                if self == 0.0 { 0.0 } else { <$ty>::signum(self) }
+            }
+            fn to_f64(self) -> f64 {
+                self as f64
+            }
+        }
+    )*};
+}
+
+signed_coord!(i8 => u8, i16 => u16, i32 => u32, i64 => u64, isize => usize);
+unsigned_coord!(u8, u16, u32, u64, usize);
+float_coord!(f32, f64);
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Point2<T> {
+    pub x: T,
+    pub y: T,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Point3<T> {
+    pub x: T,
+    pub y: T,
+    pub z: T,
+}
+
+impl<T> Point2<T> {
+    pub const fn new(x: T, y: T) -> Self {
+        Point2 { x, y }
+    }
+}
+
+impl<T> Point3<T> {
+    pub const fn new(x: T, y: T, z: T) -> Self {
+        Point3 { x, y, z }
+    }
+}
+
+/// Component-wise and scalar operators, and the distances, for both point
+/// types.
+macro_rules! point_ops {
+    ($point:ident { $($field:ident),+ }) => {
+        impl<T: Coord> $point<T> {
+            pub const ZERO: Self = $point { $($field: T::ZERO),+ };
+
+            /// Sum of the absolute differences on each axis.
+            pub fn manhattan(self, other: Self) -> T::Distance {
+                let mut sum = T::Distance::ZERO;
+                $(sum = sum + self.$field.abs_diff(other.$field);)+
+                sum
+            }
+
+            /// Largest absolute difference on any axis, the number of king
+            /// moves between the points.
+            pub fn chebyshev(self, other: Self) -> T::Distance {
+                let mut max = T::Distance::ZERO;
+                $(max = max.max(self.$field.abs_diff(other.$field));)+
+                max
+            }
+
+            pub fn euclidean(self, other: Self) -> f64 {
+                let mut sum = 0.0;
+                $(sum += self.$field.abs_diff(other.$field).to_f64().powi(2);)+
+                sum.sqrt()
+            }
+
+            /// The sign of each component, a step of at most one on each
+            /// axis towards this point from the origin.
+            pub fn signum(self) -> Self {
+                $point { $($field: self.$field.signum()),+ }
+            }
+
+            pub fn min(self, other: Self) -> Self {
+                $point { $($field: self.$field.min(other.$field)),+ }
+            }
+
+            pub fn max(self, other: Self) -> Self {
+                $point { $($field: self.$field.max(other.$field)),+ }
+            }
+        }
+
+        impl<T: Add<Output = T>> Add for $point<T> {
+            type Output = Self;
+            fn add(self, rhs: Self) -> Self {
+                $point { $($field: self.$field + rhs.$field),+ }
+            }
+        }
+
+        impl<T: Sub<Output = T>> Sub for $point<T> {
+            type Output = Self;
+            fn sub(self, rhs: Self) -> Self {
+                $point { $($field: self.$field - rhs.$field),+ }
+            }
+        }
+
+        impl<T: Neg<Output = T>> Neg for $point<T> {
+            type Output = Self;
+            fn neg(self) -> Self {
+                $point { $($field: -self.$field),+ }
+            }
+        }
+
+        impl<T: Copy + Mul<Output = T>> Mul<T> for $point<T> {
+            type Output = Self;
+            fn mul(self, rhs: T) -> Self {
+                $point { $($field: self.$field * rhs),+ }
+            }
+        }
+
+        impl<T: Copy + Div<Output = T>> Div<T> for $point<T> {
+            type Output = Self;
+            fn div(self, rhs: T) -> Self {
+                $point { $($field: self.$field / rhs),+ }
+            }
+        }
+
+        impl<T: AddAssign> AddAssign for $point<T> {
+            fn add_assign(&mut self, rhs: Self) {
+                $(self.$field += rhs.$field;)+
+            }
+        }
+
+        impl<T: SubAssign> SubAssign for $point<T> {
+            fn sub_assign(&mut self, rhs: Self) {
+                $(self.$field -= rhs.$field;)+
+            }
+        }
+
+        impl<T: Copy + MulAssign> MulAssign<T> for $point<T> {
+            fn mul_assign(&mut self, rhs: T) {
+                $(self.$field *= rhs;)+
+            }
+        }
+
+        impl<T: Copy + DivAssign> DivAssign<T> for $point<T> {
+            fn div_assign(&mut self, rhs: T) {
+                $(self.$field /= rhs;)+
+            }
+        }
+    };
+}
+
+point_ops!(Point2 { x, y });
+point_ops!(Point3 { x, y, z });
+
+impl<T: Neg<Output = T>> Point2<T> {
+    /// Quarter turn clockwise on screen.
+    pub fn rotate_right(self) -> Self {
+        Point2::new(-self.y, self.x)
+    }
+
+    /// Quarter turn counterclockwise on screen.
+    pub fn rotate_left(self) -> Self {
+        Point2::new(self.y, -self.x)
+    }
+}
+
+impl<T> From<(T, T)> for Point2<T> {
+    fn from((x, y): (T, T)) -> Self {
+        Point2 { x, y }
+    }
+}
+
+impl<T> From<Point2<T>> for (T, T) {
+    fn from(point: Point2<T>) -> Self {
+        (point.x, point.y)
+    }
+}
+
+impl<T> From<(T, T, T)> for Point3<T> {
+    fn from((x, y, z): (T, T, T)) -> Self {
+        Point3 { x, y, z }
+    }
+}
+
+impl<T: fmt::Display> fmt::Display for Point2<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "({}, {})", self.x, self.y)
+    }
+}
+
+impl<T: fmt::Display> fmt::Display for Point3<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "({}, {}, {})", self.x, self.y, self.z)
+    }
+}
+
+/// The four orthogonal directions.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Direction {
+    Up,
+    Right,
+    Down,
+    Left,
+}
+
+impl Direction {
+    /// Clockwise from up.
+    pub const ALL: [Direction; 4] = [
+        Direction::Up,
+        Direction::Right,
+        Direction::Down,
+        Direction::Left,
+    ];
+
+    pub fn offset(self) -> Point2<isize> {
+        match self {
+            Direction::Up => Point2::new(0, -1),
+            Direction::Right => Point2::new(1, 0),
+            Direction::Down => Point2::new(0, 1),
+            Direction::Left => Point2::new(-1, 0),
+        }
+    }
+
+    pub fn turn_right(self) -> Self {
+        Self::ALL[(self as usize + 1) % 4]
+    }
+
+    pub fn turn_left(self) -> Self {
+        Self::ALL[(self as usize + 3) % 4]
+    }
+
+    pub fn reverse(self) -> Self {
+        Self::ALL[(self as usize + 2) % 4]
+    }
+}
+
+impl TryFrom<char> for Direction {
+    type Error = anyhow::Error;
+
+    /// Accepts `U`, `R`, `D` and `L`.
+    fn try_from(c: char) -> anyhow::Result<Self> {
+        match c {
+            'U' => Ok(Direction::Up),
+            'R' => Ok(Direction::Right),
+            'D' => Ok(Direction::Down),
+            'L' => Ok(Direction::Left),
+            _ => Err(invalid(format!("{:?} is not a direction", c))),
+        }
+    }
+}
+
+impl FromStr for Direction {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> anyhow::Result<Self> {
+        let mut chars = s.chars();
+        match (chars.next(), chars.next()) {
+            (Some(c), None) => c.try_into(),
+            _ => Err(invalid(format!("{:?} is not a direction", s))),
+        }
+    }
+}
+
+/// The orthogonal and diagonal directions.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Direction8 {
+    Up,
+    UpRight,
+    Right,
+    DownRight,
+    Down,
+    DownLeft,
+    Left,
+    UpLeft,
+}
+
+impl Direction8 {
+    /// Clockwise from up.
+    pub const ALL: [Direction8; 8] = [
+        Direction8::Up,
+        Direction8::UpRight,
+        Direction8::Right,
+        Direction8::DownRight,
+        Direction8::Down,
+        Direction8::DownLeft,
+        Direction8::Left,
+        Direction8::UpLeft,
+    ];
+
+    pub fn offset(self) -> Point2<isize> {
+        const OFFSETS: [(isize, isize); 8] = [
+            (0, -1),
+            (1, -1),
+            (1, 0),
+            (1, 1),
+            (0, 1),
+            (-1, 1),
+            (-1, 0),
+            (-1, -1),
+        ];
+        OFFSETS[self as usize].into()
+    }
+
+    /// An eighth of a turn clockwise.
+    pub fn turn_right(self) -> Self {
+        Self::ALL[(self as usize + 1) % 8]
+    }
+
+    /// An eighth of a turn counterclockwise.
+    pub fn turn_left(self) -> Self {
+        Self::ALL[(self as usize + 7) % 8]
+    }
+
+    pub fn reverse(self) -> Self {
+        Self::ALL[(self as usize + 4) % 8]
+    }
+}
+
+impl From<Direction> for Direction8 {
+    fn from(direction: Direction) -> Self {
+        Direction8::ALL[direction as usize * 2]
+    }
+}
+
+impl From<Direction> for Point2<isize> {
+    fn from(direction: Direction) -> Self {
+        direction.offset()
+    }
+}
+
+impl From<Direction8> for Point2<isize> {
+    fn from(direction: Direction8) -> Self {
+        direction.offset()
+    }
+}
+
+/// An axis-aligned box, inclusive of both corners.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Bounds<T> {
+    pub min: Point2<T>,
+    pub max: Point2<T>,
+}
+
+impl<T: Coord> Bounds<T> {
+    pub fn new(a: Point2<T>, b: Point2<T>) -> Self {
+        Bounds {
+            min: a.min(b),
+            max: a.max(b),
+        }
+    }
+
+    /// The smallest box holding every point, or `None` if there are none.
+    pub fn from_points(points: impl IntoIterator<Item = Point2<T>>) -> Option<Self> {
+        let mut points = points.into_iter();
+        let first = points.next()?;
+        let mut bounds = Bounds::new(first, first);
+        for point in points {
+            bounds.extend(point);
+        }
+        Some(bounds)
+    }
+
+    /// Grows the box to hold `point`.
+    pub fn extend(&mut self, point: Point2<T>) {
+        self.min = self.min.min(point);
+        self.max = self.max.max(point);
+    }
+
+    pub fn contains(&self, point: Point2<T>) -> bool {
+        self.min.x <= point.x
+            && point.x <= self.max.x
+            && self.min.y <= point.y
+            && point.y <= self.max.y
+    }
+
+    pub fn width(&self) -> T {
+        self.max.x - self.min.x + T::ONE
+    }
+
+    pub fn height(&self) -> T {
+        self.max.y - self.min.y + T::ONE
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn arithmetic() {
+        let mut a = Point2::new(1, 2);
+        let b = Point2::new(3, -4);
+        assert_eq!(a + b, Point2::new(4, -2));
+        assert_eq!(a - b, Point2::new(-2, 6));
+        assert_eq!(-b, Point2::new(-3, 4));
+        assert_eq!(b * 2, Point2::new(6, -8));
+        assert_eq!(b / 2, Point2::new(1, -2));
+        a += b;
+        a *= 3;
+        assert_eq!(a, Point2::new(12, -6));
+        assert_eq!(
+            Point3::new(1, 2, 3) + Point3::new(1, 1, 1),
+            Point3::new(2, 3, 4)
+        );
+        assert_eq!(Point2::new(5, -3).signum(), Point2::new(1, -1));
+        assert_eq!(Point2::new(1, 0).rotate_right(), Point2::new(0, 1));
+    }
+
+    #[test]
+    fn distances() {
+        let a = Point2::new(0i32, 0);
+        let b = Point2::new(3, -4);
+        assert_eq!(a.manhattan(b), 7);
+        assert_eq!(a.chebyshev(b), 4);
+        assert_eq!(a.euclidean(b), 5.0);
+        assert_eq!(Point3::new(1u32, 5, 2).manhattan(Point3::new(4, 1, 2)), 7);
+
+        // Far-apart signed coordinates don't overflow.
+        assert_eq!(Coord::abs_diff(i32::MIN, 1), 2_147_483_649u32);
+        assert_eq!(Coord::abs_diff(i8::MAX, i8::MIN), u8::MAX);
+        assert_eq!(Coord::abs_diff(isize::MIN, isize::MAX), usize::MAX);
+        let far = Point2::new(i64::MIN, i64::MAX);
+        assert_eq!(far.chebyshev(Point2::new(i64::MAX, 0)), u64::MAX);
+        assert_eq!(far.manhattan(Point2::new(0, 0)), u64::MAX);
+    }
+
+    #[test]
+    fn directions() {
+        assert_eq!("U".parse::<Direction>().unwrap(), Direction::Up);
+        assert_eq!(Direction::try_from('L').unwrap(), Direction::Left);
+        for compass in ['N', 'E', 'S', 'W'] {
+            assert!(Direction::try_from(compass).is_err());
+        }
+        assert!("X".parse::<Direction>().is_err());
+        assert!("UR".parse::<Direction>().is_err());
+        assert_eq!(Direction::Left.turn_right(), Direction::Up);
+        assert_eq!(Direction::Up.turn_left(), Direction::Left);
+        assert_eq!(Direction::Right.reverse(), Direction::Left);
+        assert_eq!(
+            Direction::Right.offset().rotate_right(),
+            Direction::Down.offset()
+        );
+        assert_eq!(Direction8::from(Direction::Down), Direction8::Down);
+        assert_eq!(Direction8::UpLeft.turn_right(), Direction8::Up);
+        assert_eq!(Direction8::DownRight.offset(), Point2::new(1, 1));
+    }
+
+    #[test]
+    fn bounds() {
+        let points = [Point2::new(2, 3), Point2::new(-1, 5), Point2::new(0, 0)];
+        let bounds = Bounds::from_points(points).unwrap();
+        assert_eq!(bounds.min, Point2::new(-1, 0));
+        assert_eq!(bounds.max, Point2::new(2, 5));
+        assert_eq!((bounds.width(), bounds.height()), (4, 6));
+        assert!(bounds.contains(Point2::new(0, 4)));
+        assert!(!bounds.contains(Point2::new(3, 4)));
+        assert_eq!(Bounds::<i32>::from_points([]), None);
+    }
+}
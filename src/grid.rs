@@ -0,0 +1,272 @@
+//! A dense 2D grid stored row-major in one `Vec`.
+//!
+//! Positions are points with `x` the column and `y` the row, counting from
+//! the top left. Steps are signed offsets such as [`Direction::offset`].
+//! Anything that converts into a [`Pos`] or [`Step`], including plain
+//! `(x, y)` tuples and directions, is accepted.
+
+use std::fmt;
+use std::ops::{Index, IndexMut};
+
+use anyhow::Result;
+
+use crate::error::invalid;
+#[cfg(doc)]
+use crate::geom::Direction;
+use crate::geom::Point2;
+
+pub type Pos = Point2<usize>;
+pub type Step = Point2<isize>;
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Grid<T> {
+    width: usize,
+    height: usize,
+    cells: Vec<T>,
+}
+
+impl<T: Clone> Grid<T> {
+    pub fn new(width: usize, height: usize, fill: T) -> Self {
+        Grid {
+            width,
+            height,
+            cells: vec![fill; width * height],
+        }
+    }
+}
+
+impl<T> Grid<T> {
+    /// Parses one cell per character, one row per line. Fails if the rows
+    /// differ in length or `cell` rejects a character.
+    pub fn parse(text: &str, mut cell: impl FnMut(char) -> Option<T>) -> Result<Self> {
+        let mut cells = Vec::with_capacity(text.len());
+        let mut width = None;
+        let mut height = 0;
+        for (y, line) in text.lines().enumerate() {
+            let before = cells.len();
+            for (x, c) in line.chars().enumerate() {
+                let value = cell(c).ok_or_else(|| {
+                    invalid(format!(
+                        "unexpected {:?} at line {}, column {}",
+                        c,
+                        y + 1,
+                        x + 1
+                    ))
+                })?;
+                cells.push(value);
+            }
+            let len = cells.len() - before;
+            match width {
+                None => width = Some(len),
+                Some(width) if width != len => {
+                    return Err(invalid(format!(
+                        "line {} has {} cells, expected {}",
+                        y + 1,
+                        len,
+                        width
+                    )))
+                }
+                Some(_) => {}
+            }
+            height += 1;
+        }
+        Ok(Grid {
+            width: width.unwrap_or(0),
+            height,
+            cells,
+        })
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    pub fn contains(&self, pos: impl Into<Pos>) -> bool {
+        let pos = pos.into();
+        pos.x < self.width && pos.y < self.height
+    }
+
+    pub fn get(&self, pos: impl Into<Pos>) -> Option<&T> {
+        let pos = pos.into();
+        if self.contains(pos) {
+            self.cells.get(pos.y * self.width + pos.x)
+        } else {
+            None
+        }
+    }
+
+    pub fn get_mut(&mut self, pos: impl Into<Pos>) -> Option<&mut T> {
+        let pos = pos.into();
+        if self.contains(pos) {
+            self.cells.get_mut(pos.y * self.width + pos.x)
+        } else {
+            None
+        }
+    }
+
+    /// The position one `step` from `pos`, if it's still on the grid.
+    pub fn step(&self, pos: impl Into<Pos>, step: impl Into<Step>) -> Option<Pos> {
+        let (pos, step) = (pos.into(), step.into());
+        let next = Point2::new(
+            pos.x.checked_add_signed(step.x)?,
+            pos.y.checked_add_signed(step.y)?,
+        );
+        self.contains(next).then_some(next)
+    }
+
+    pub fn row(&self, y: usize) -> &[T] {
+        &self.cells[y * self.width..(y + 1) * self.width]
+    }
+
+    pub fn rows(&self) -> impl Iterator<Item = &[T]> + '_ {
+        (0..self.height).map(|y| self.row(y))
+    }
+
+    pub fn column(&self, x: usize) -> impl Iterator<Item = &T> + '_ {
+        assert!(x < self.width, "column {} out of bounds", x);
+        self.cells.iter().skip(x).step_by(self.width)
+    }
+
+    /// Every position, row by row.
+    pub fn positions(&self) -> impl Iterator<Item = Pos> {
+        let width = self.width;
+        (0..self.height).flat_map(move |y| (0..width).map(move |x| Point2::new(x, y)))
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (Pos, &T)> + '_ {
+        self.positions().zip(self.cells.iter())
+    }
+
+    /// The positions walked by repeating `step` from `from`, not including
+    /// `from`, up to the edge of the grid.
+    pub fn ray(
+        &self,
+        from: impl Into<Pos>,
+        step: impl Into<Step>,
+    ) -> impl Iterator<Item = Pos> + '_ {
+        let step = step.into();
+        std::iter::successors(self.step(from, step), move |pos| self.step(*pos, step))
+    }
+
+    /// The positions one of `steps` away from `pos` that are on the grid,
+    /// e.g. `grid.neighbors(pos, Direction::ALL)`.
+    pub fn neighbors<'a, I>(&'a self, pos: Pos, steps: I) -> impl Iterator<Item = Pos> + 'a
+    where
+        I: IntoIterator,
+        I::Item: Into<Step>,
+        I::IntoIter: 'a,
+    {
+        steps
+            .into_iter()
+            .filter_map(move |step| self.step(pos, step))
+    }
+
+    /// Draws the grid one character per cell, one line per row.
+    pub fn render(&self, mut cell: impl FnMut(&T) -> char) -> String {
+        let mut out = String::with_capacity((self.width + 1) * self.height);
+        for row in self.rows() {
+            out.extend(row.iter().map(&mut cell));
+            out.push('\n');
+        }
+        out
+    }
+}
+
+impl<T> Index<Pos> for Grid<T> {
+    type Output = T;
+
+    fn index(&self, pos: Pos) -> &T {
+        self.get(pos)
+            .unwrap_or_else(|| panic!("{} is outside the {}x{} grid", pos, self.width, self.height))
+    }
+}
+
+impl<T> IndexMut<Pos> for Grid<T> {
+    fn index_mut(&mut self, pos: Pos) -> &mut T {
+        let (width, height) = (self.width, self.height);
+        self.get_mut(pos)
+            .unwrap_or_else(|| panic!("{} is outside the {}x{} grid", pos, width, height))
+    }
+}
+
+impl<T> Index<(usize, usize)> for Grid<T> {
+    type Output = T;
+
+    fn index(&self, pos: (usize, usize)) -> &T {
+        &self[Pos::from(pos)]
+    }
+}
+
+impl<T> IndexMut<(usize, usize)> for Grid<T> {
+    fn index_mut(&mut self, pos: (usize, usize)) -> &mut T {
+        &mut self[Pos::from(pos)]
+    }
+}
+
+impl<T: fmt::Display> fmt::Display for Grid<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for row in self.rows() {
+            for cell in row {
+                write!(f, "{}", cell)?;
+            }
+            writeln!(f)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::geom::{Direction, Direction8};
+
+    fn digits() -> Grid<u32> {
+        Grid::parse("123\n456\n", |c| c.to_digit(10)).unwrap()
+    }
+
+    #[test]
+    fn parse_and_access() {
+        let grid = digits();
+        assert_eq!((grid.width(), grid.height()), (3, 2));
+        assert_eq!(grid[(2, 1)], 6);
+        assert_eq!(grid.get((3, 0)), None);
+        assert_eq!(grid.row(1), &[4, 5, 6]);
+        assert_eq!(grid.column(1).copied().collect::<Vec<_>>(), vec![2, 5]);
+        assert_eq!(grid.to_string(), "123\n456\n");
+
+        let err = Grid::parse("12\n3\n", |c| c.to_digit(10)).unwrap_err();
+        assert_eq!(err.to_string(), "line 2 has 1 cells, expected 2");
+        let err = Grid::parse("12\n3x\n", |c| c.to_digit(10)).unwrap_err();
+        assert_eq!(err.to_string(), "unexpected 'x' at line 2, column 2");
+    }
+
+    #[test]
+    fn rays_and_neighbors() {
+        let grid = digits();
+        assert_eq!(
+            grid.ray((0, 0), Direction::Right).collect::<Vec<_>>(),
+            vec![Point2::new(1, 0), Point2::new(2, 0)]
+        );
+        assert_eq!(grid.ray((0, 0), Direction::Left).count(), 0);
+        assert_eq!(
+            grid.ray((0, 0), (1, 1)).collect::<Vec<_>>(),
+            vec![Point2::new(1, 1)]
+        );
+        assert_eq!(grid.neighbors(Point2::new(0, 0), Direction::ALL).count(), 2);
+        assert_eq!(
+            grid.neighbors(Point2::new(1, 0), Direction8::ALL).count(),
+            5
+        );
+    }
+
+    #[test]
+    fn render() {
+        let mut grid = Grid::new(2, 2, false);
+        grid[(1, 0)] = true;
+        assert_eq!(grid.render(|on| if *on { '#' } else { '.' }), ".#\n..\n");
+    }
+}
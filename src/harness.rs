@@ -0,0 +1,195 @@
+//! A headless Bevy app for running puzzle simulations as ECS systems.
+//!
+//! The app only has [`MinimalPlugins`], so it needs no window or GPU, and it
+//! never runs its own loop: [`Harness::run`] advances exactly the number of
+//! schedule ticks asked for. Task pools get a single thread so systems run in
+//! the same order every time. `Time` still follows the wall clock; systems
+//! that should be reproducible count [`Tick`]s instead.
+
+use std::str::FromStr;
+
+use anyhow::Result;
+use bevy::ecs::schedule::IntoSystemDescriptor;
+use bevy::prelude::*;
+
+/// How many ticks the harness has run, counted before any other system.
+#[derive(Resource, Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct Tick(pub u64);
+
+fn count_ticks(mut tick: ResMut<Tick>) {
+    tick.0 += 1;
+}
+
+pub struct Harness {
+    app: App,
+}
+
+impl Default for Harness {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Harness {
+    pub fn new() -> Self {
+        let mut app = App::new();
+        app.add_plugins(MinimalPlugins.set(CorePlugin {
+            task_pool_options: TaskPoolOptions::with_num_threads(1),
+        }))
+        .init_resource::<Tick>()
+        .add_system_to_stage(CoreStage::First, count_ticks);
+        Harness { app }
+    }
+
+    /// Parses `input` into a resource, e.g. a day's puzzle model.
+    pub fn parse<R>(self, input: &str) -> Result<Self>
+    where
+        R: Resource + FromStr<Err = anyhow::Error>,
+    {
+        Ok(self.with_resource(input.parse::<R>()?))
+    }
+
+    pub fn with_resource<R: Resource>(mut self, resource: R) -> Self {
+        self.app.insert_resource(resource);
+        self
+    }
+
+    /// Adds a system to the update stage.
+    pub fn with_system<Params>(mut self, system: impl IntoSystemDescriptor<Params>) -> Self {
+        self.app.add_system(system);
+        self
+    }
+
+    /// Runs the schedule once.
+    pub fn tick(&mut self) {
+        self.app.update();
+    }
+
+    /// Runs the schedule `ticks` times.
+    pub fn run(&mut self, ticks: u64) -> &mut Self {
+        for _ in 0..ticks {
+            self.tick();
+        }
+        self
+    }
+
+    /// Ticks until `done` holds for the world or `limit` ticks have run,
+    /// returning whether it held.
+    pub fn run_until(&mut self, limit: u64, mut done: impl FnMut(&World) -> bool) -> bool {
+        for _ in 0..limit {
+            if done(&self.app.world) {
+                return true;
+            }
+            self.tick();
+        }
+        done(&self.app.world)
+    }
+
+    pub fn ticks(&self) -> u64 {
+        self.resource::<Tick>().0
+    }
+
+    /// The resource of type `R`; panics if it was never inserted.
+    pub fn resource<R: Resource>(&self) -> &R {
+        self.app.world.resource::<R>()
+    }
+
+    pub fn get_resource<R: Resource>(&self) -> Option<&R> {
+        self.app.world.get_resource::<R>()
+    }
+
+    pub fn world(&self) -> &World {
+        &self.app.world
+    }
+
+    pub fn world_mut(&mut self) -> &mut World {
+        &mut self.app.world
+    }
+
+    /// The underlying app, for plugins or stages the builder doesn't cover.
+    pub fn app_mut(&mut self) -> &mut App {
+        &mut self.app
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashSet;
+
+    use crate::days::y2022::day9;
+    use crate::geom::Point2;
+    use crate::runner::Cancel;
+
+    type Knot = Point2<isize>;
+
+    /// Day 9's rope, moving the head one step per tick.
+    #[derive(Resource)]
+    struct Rope<const KNOTS: usize> {
+        steps: std::vec::IntoIter<Knot>,
+        knots: [Knot; KNOTS],
+        visited: HashSet<Knot>,
+    }
+
+    impl<const KNOTS: usize> FromStr for Rope<KNOTS> {
+        type Err = anyhow::Error;
+
+        fn from_str(input: &str) -> Result<Self> {
+            let motions = day9::parse(input)?;
+            Ok(Rope {
+                steps: day9::steps(&motions).collect::<Vec<_>>().into_iter(),
+                knots: [Knot::ZERO; KNOTS],
+                visited: HashSet::from([Knot::ZERO]),
+            })
+        }
+    }
+
+    fn drag<const KNOTS: usize>(mut rope: ResMut<Rope<KNOTS>>) {
+        let rope = &mut *rope;
+        if let Some(step) = rope.steps.next() {
+            rope.knots[0] += step;
+            for i in 1..KNOTS {
+                rope.knots[i] = day9::update_tail(rope.knots[i - 1], rope.knots[i]);
+            }
+            rope.visited.insert(rope.knots[KNOTS - 1]);
+        }
+    }
+
+    fn visited<const KNOTS: usize>(harness: &Harness) -> usize {
+        harness.resource::<Rope<KNOTS>>().visited.len()
+    }
+
+    #[test]
+    fn steps_a_fixed_number_of_ticks() {
+        let example = include_str!("../inputs/2022/day9_example.txt");
+        let mut harness = Harness::new()
+            .parse::<Rope<2>>(example)
+            .unwrap()
+            .with_system(drag::<2>);
+        // "R 4" leaves the tail three squares along.
+        harness.run(4);
+        assert_eq!((harness.ticks(), visited::<2>(&harness)), (4, 4));
+        harness.run(24 - 4);
+        assert_eq!((harness.ticks(), visited::<2>(&harness)), (24, 13));
+    }
+
+    #[test]
+    fn runs_until_a_condition() {
+        let input = include_str!("../inputs/2022/day9.txt");
+        let mut harness = Harness::new()
+            .parse::<Rope<10>>(input)
+            .unwrap()
+            .with_system(drag::<10>);
+        let done = |world: &World| world.resource::<Rope<10>>().steps.len() == 0;
+        assert!(harness.run_until(100_000, done));
+        let model = day9::parse(input).unwrap();
+        assert_eq!(
+            visited::<10>(&harness),
+            day9::part2(&model, &Cancel::new()).unwrap()
+        );
+        assert_eq!(harness.ticks() as usize, day9::steps(&model).count());
+
+        assert!(!Harness::new().run_until(3, |_| false));
+        assert!(Harness::new().parse::<Rope<2>>("R x").is_err());
+    }
+}
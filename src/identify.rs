@@ -0,0 +1,138 @@
+use std::collections::HashSet;
+
+use crate::days;
+use crate::lint;
+
+/// How well an input fits one day's format, from 0 to 1.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Candidate {
+    pub year: u16,
+    pub day: u8,
+    pub score: f64,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Identification {
+    pub year: u16,
+    pub day: u8,
+    /// Share of the total score held by the best candidate.
+    pub confidence: f64,
+    /// Every day with a non-zero score, best first.
+    pub candidates: Vec<Candidate>,
+}
+
+/// Scores `content` against every registered day. A day is a candidate when
+/// its parser accepts the content, and its score is the fraction of lines its
+/// lint rules leave clean.
+pub fn scores(content: &str) -> Vec<Candidate> {
+    let lines = content.lines().count();
+    if lines == 0 {
+        return Vec::new();
+    }
+
+    let mut candidates = days::YEARS
+        .iter()
+        .flat_map(|year| year.days.iter().map(move |info| (year.year, info)))
+        .filter(|(_, info)| (info.parse)(content).is_ok())
+        .map(|(year, info)| {
+            let violations = lint::lint(year, info.day, content).unwrap_or_default();
+            let dirty = violations
+                .iter()
+                .map(|violation| violation.line)
+                .collect::<HashSet<_>>()
+                .len();
+
+            Candidate {
+                year,
+                day: info.day,
+                score: (1.0 - (dirty as f64 / lines as f64)).max(0.0),
+            }
+        })
+        .filter(|candidate| candidate.score > 0.0)
+        .collect::<Vec<_>>();
+
+    candidates.sort_by(|a, b| {
+        b.score
+            .total_cmp(&a.score)
+            .then((a.year, a.day).cmp(&(b.year, b.day)))
+    });
+    candidates
+}
+
+/// Picks the puzzle `content` most likely belongs to, or `None` if no day's
+/// parser accepts it.
+pub fn identify(content: &str) -> Option<Identification> {
+    let candidates = scores(content);
+    let best = candidates.first()?;
+    let total = candidates.iter().map(|c| c.score).sum::<f64>();
+
+    Some(Identification {
+        year: best.year,
+        day: best.day,
+        confidence: best.score / total,
+        candidates,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn shipped_inputs() {
+        let inputs = [
+            (1, include_str!("../inputs/2022/day1.txt")),
+            (2, include_str!("../inputs/2022/day2.txt")),
+            (3, include_str!("../inputs/2022/day3.txt")),
+            (4, include_str!("../inputs/2022/day4.txt")),
+            (5, include_str!("../inputs/2022/day5.txt")),
+            (6, include_str!("../inputs/2022/day6.txt")),
+            (7, include_str!("../inputs/2022/day7.txt")),
+            (8, include_str!("../inputs/2022/day8.txt")),
+            (9, include_str!("../inputs/2022/day9.txt")),
+        ];
+        for (day, content) in inputs {
+            let found = identify(content).unwrap();
+            assert_eq!(
+                (found.year, found.day),
+                (2022, day),
+                "{:?}",
+                found.candidates
+            );
+            assert!(found.confidence > 0.5, "{:?}", found);
+        }
+    }
+
+    #[test]
+    fn follows_the_parsers() {
+        // Whitespace-only lines separate elves, as `blocks` reads them.
+        let found = identify("1000\n  \n2000\n\t\n\n3000\n").unwrap();
+        assert_eq!((found.year, found.day), (2022, 1));
+        assert_eq!(found.confidence, 1.0);
+
+        // Rope motions only go U, R, D and L.
+        assert!(days::y2022::day9::parse("N 4\nE 2\n").is_err());
+        assert_eq!(identify("N 4\nE 2\n"), None);
+
+        // A short grid of digits is just as good a list of calories.
+        let found = identify(include_str!("../inputs/2022/day8_example.txt")).unwrap();
+        let days = found.candidates.iter().map(|c| c.day).collect::<Vec<_>>();
+        assert_eq!(days, [1, 8]);
+    }
+
+    #[test]
+    fn unknown_format() {
+        assert_eq!(identify("hello, world\n"), None);
+        assert_eq!(identify(""), None);
+    }
+
+    #[test]
+    fn non_ascii_input() {
+        assert_eq!(identify("héllo\n"), None);
+        assert_eq!(identify("☃ ¤\nnaïve\n"), None);
+
+        let mut content = include_str!("../inputs/2022/day3_example.txt").to_string();
+        content.push_str("vJrwpWtwJgWrhcsFMMfFFhFp\njqHRNqRjqzjGDLGLrsFMfFZSrLrFZsSLé\n");
+        assert_eq!(identify(&content), None);
+    }
+}
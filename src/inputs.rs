@@ -0,0 +1,59 @@
+//! Where puzzle inputs live on disk, and the answers expected for them.
+//!
+//! Inputs are kept per event as `inputs/{year}/day{day}.txt`, with examples
+//! beside them. `inputs/answers.json` lists known answers for any of those
+//! files.
+
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result};
+use serde::Deserialize;
+
+pub const ROOT: &str = "inputs";
+
+pub fn dir(year: u16) -> PathBuf {
+    Path::new(ROOT).join(year.to_string())
+}
+
+/// The real puzzle input for `day` of `year`.
+pub fn path(year: u16, day: u8) -> PathBuf {
+    dir(year).join(format!("day{}.txt", day))
+}
+
+/// One manifest entry: the answers to a day's parts for one input file.
+#[derive(Debug, Clone, Deserialize)]
+pub struct Expected {
+    pub year: u16,
+    pub day: u8,
+    /// File name within the year's input directory.
+    pub input: String,
+    pub part1: Option<String>,
+    pub part2: Option<String>,
+}
+
+impl Expected {
+    pub fn path(&self) -> PathBuf {
+        dir(self.year).join(&self.input)
+    }
+
+    pub fn answer(&self, part: u8) -> Option<&str> {
+        match part {
+            1 => self.part1.as_deref(),
+            2 => self.part2.as_deref(),
+            _ => None,
+        }
+    }
+
+    /// Whether this is the real input rather than a puzzle example.
+    pub fn is_real(&self) -> bool {
+        self.path() == path(self.year, self.day)
+    }
+}
+
+/// Loads `inputs/answers.json`.
+pub fn manifest() -> Result<Vec<Expected>> {
+    let path = Path::new(ROOT).join("answers.json");
+    let json =
+        std::fs::read_to_string(&path).with_context(|| format!("reading {}", path.display()))?;
+    Ok(serde_json::from_str(&json)?)
+}
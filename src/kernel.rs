@@ -0,0 +1,1612 @@
+//! Runs WGSL compute shaders on the CPU by interpreting naga's IR, so the
+//! kernels under `assets/shaders` can be tested and used without a GPU.
+//!
+//! Only what our shaders need is covered: storage and uniform buffers,
+//! `rgba8unorm` storage textures in group 0, scalar and vector math on `i32`,
+//! `u32`, `f32` and `bool`, function calls, loops and workgroup dispatch.
+//! Invocations run one after another in dispatch order, so barriers do
+//! nothing and a kernel that races on the GPU sees one fixed interleaving.
+//! Out-of-bounds buffer and texture reads give zero and out-of-bounds writes
+//! are dropped, as robust buffer access allows.
+
+use std::collections::{BTreeMap, HashMap};
+use std::path::Path;
+
+use anyhow::{anyhow, bail, Context, Result};
+use naga::valid::{Capabilities, ValidationFlags, Validator};
+use naga::{
+    AddressSpace, ArraySize, BinaryOperator, Binding, Block, BuiltIn, ConstantInner, Expression,
+    Function, GlobalVariable, Handle, ImageClass, ImageQuery, MathFunction, Module,
+    RelationalFunction, ScalarKind, ScalarValue, ShaderStage, Statement, StorageFormat, SwitchCase,
+    SwitchValue, Type, TypeInner, UnaryOperator,
+};
+
+use crate::raster::{Canvas, Rgb};
+
+/// Loop iterations one invocation may run before it's assumed to be stuck.
+const STEP_LIMIT: u64 = 1 << 24;
+
+/// A parsed and validated WGSL module.
+pub struct Kernel {
+    module: Module,
+    step_limit: u64,
+}
+
+impl Kernel {
+    pub fn parse(source: &str) -> Result<Self> {
+        let module = naga::front::wgsl::parse_str(source)
+            .map_err(|err| anyhow!(err.emit_to_string(source)))?;
+        Validator::new(ValidationFlags::all(), Capabilities::empty())
+            .validate(&module)
+            .map_err(|err| anyhow!(err.emit_to_string(source)))?;
+        Ok(Kernel {
+            module,
+            step_limit: STEP_LIMIT,
+        })
+    }
+
+    pub fn load(path: &Path) -> Result<Self> {
+        let source = std::fs::read_to_string(path)
+            .with_context(|| format!("couldn't read {}", path.display()))?;
+        Self::parse(&source).with_context(|| format!("in {}", path.display()))
+    }
+
+    /// Caps the loop iterations each invocation may run.
+    pub fn with_step_limit(mut self, steps: u64) -> Self {
+        self.step_limit = steps;
+        self
+    }
+
+    /// The workgroup size of the compute entry point `entry`.
+    pub fn workgroup_size(&self, entry: &str) -> Option<[u32; 3]> {
+        self.entry_point(entry).map(|entry| entry.workgroup_size)
+    }
+
+    fn entry_point(&self, entry: &str) -> Option<&naga::EntryPoint> {
+        self.module
+            .entry_points
+            .iter()
+            .find(|point| point.name == entry && point.stage == ShaderStage::Compute)
+    }
+
+    /// Runs `entry` over a grid of `workgroups`, reading and writing the
+    /// resources in `bindings`.
+    pub fn dispatch(
+        &self,
+        entry: &str,
+        workgroups: [u32; 3],
+        bindings: &mut Bindings,
+    ) -> Result<()> {
+        let entry_point = self
+            .entry_point(entry)
+            .ok_or_else(|| anyhow!("no compute entry point {:?}", entry))?;
+        self.check_bindings(bindings)?;
+
+        let size = entry_point.workgroup_size;
+        let mut machine = Machine {
+            module: &self.module,
+            bindings,
+            globals: HashMap::new(),
+            locals: Vec::new(),
+            steps: 0,
+            step_limit: self.step_limit,
+        };
+        for group in grid(workgroups) {
+            machine.reset_globals(AddressSpace::WorkGroup)?;
+            for local in grid(size) {
+                machine.reset_globals(AddressSpace::Private)?;
+                machine.steps = 0;
+                let global = [0, 1, 2].map(|i| group[i].wrapping_mul(size[i]) + local[i]);
+                let index = local[0] + size[0] * (local[1] + size[1] * local[2]);
+                let args = entry_point
+                    .function
+                    .arguments
+                    .iter()
+                    .map(|arg| match &arg.binding {
+                        Some(Binding::BuiltIn(builtin)) => Ok(match builtin {
+                            BuiltIn::GlobalInvocationId => Value::uvec(global),
+                            BuiltIn::LocalInvocationId => Value::uvec(local),
+                            BuiltIn::LocalInvocationIndex => Value::U32(index),
+                            BuiltIn::WorkGroupId => Value::uvec(group),
+                            BuiltIn::WorkGroupSize => Value::uvec(size),
+                            BuiltIn::NumWorkGroups => Value::uvec(workgroups),
+                            other => bail!("unsupported builtin {:?}", other),
+                        }),
+                        _ => bail!("entry point arguments must be builtins"),
+                    })
+                    .collect::<Result<_>>()?;
+                machine
+                    .call(&entry_point.function, args)
+                    .with_context(|| format!("{} at {:?}", entry, global))?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Makes sure every resource the module declares is bound, and is one
+    /// this interpreter can handle.
+    fn check_bindings(&self, bindings: &Bindings) -> Result<()> {
+        for (_, global) in self.module.global_variables.iter() {
+            if global.binding.is_none() {
+                continue;
+            }
+            let binding = binding_of(global)?;
+            let name = global.name.as_deref().unwrap_or("?");
+            match (global.space, bindings.0.get(&binding)) {
+                (
+                    AddressSpace::Storage { .. } | AddressSpace::Uniform,
+                    Some(Resource::Buffer(_)),
+                ) => {}
+                (AddressSpace::Handle, Some(Resource::Texture(_))) => {
+                    match &self.module.types[global.ty].inner {
+                        TypeInner::Image {
+                            class:
+                                ImageClass::Storage {
+                                    format: StorageFormat::Rgba8Unorm,
+                                    ..
+                                },
+                            arrayed: false,
+                            ..
+                        } => {}
+                        other => bail!("{} has unsupported image type {:?}", name, other),
+                    }
+                }
+                (_, None) => bail!("nothing bound at binding {} for {}", binding, name),
+                (_, Some(_)) => bail!("wrong kind of resource at binding {} for {}", binding, name),
+            }
+        }
+        Ok(())
+    }
+}
+
+fn grid([x, y, z]: [u32; 3]) -> impl Iterator<Item = [u32; 3]> {
+    (0..z).flat_map(move |k| (0..y).flat_map(move |j| (0..x).map(move |i| [i, j, k])))
+}
+
+/// The binding number of a resource, which must be in group 0.
+fn binding_of(global: &GlobalVariable) -> Result<u32> {
+    match &global.binding {
+        Some(binding) if binding.group == 0 => Ok(binding.binding),
+        Some(binding) => bail!("only group 0 is supported, not {}", binding.group),
+        None => bail!("{:?} has no binding", global.name),
+    }
+}
+
+/// An `rgba8unorm` storage texture.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Texture {
+    width: u32,
+    height: u32,
+    texels: Vec<[u8; 4]>,
+}
+
+impl Texture {
+    pub fn new(width: u32, height: u32, texel: [u8; 4]) -> Self {
+        Texture {
+            width,
+            height,
+            texels: vec![texel; (width * height) as usize],
+        }
+    }
+
+    pub fn width(&self) -> u32 {
+        self.width
+    }
+
+    pub fn height(&self) -> u32 {
+        self.height
+    }
+
+    fn index(&self, x: i64, y: i64) -> Option<usize> {
+        let inside = (0..self.width as i64).contains(&x) && (0..self.height as i64).contains(&y);
+        inside.then(|| (y * self.width as i64 + x) as usize)
+    }
+
+    pub fn get(&self, x: u32, y: u32) -> Option<[u8; 4]> {
+        self.index(x as i64, y as i64).map(|idx| self.texels[idx])
+    }
+
+    pub fn set(&mut self, x: u32, y: u32, texel: [u8; 4]) {
+        if let Some(idx) = self.index(x as i64, y as i64) {
+            self.texels[idx] = texel;
+        }
+    }
+
+    /// The color channels as an image, ignoring alpha.
+    pub fn to_canvas(&self) -> Canvas {
+        let mut canvas = Canvas::new(self.width as usize, self.height as usize, Rgb::BLACK);
+        for (idx, [r, g, b, _]) in self.texels.iter().enumerate() {
+            let (x, y) = (idx as u32 % self.width, idx as u32 / self.width);
+            canvas.set(x as i32, y as i32, Rgb(*r, *g, *b));
+        }
+        canvas
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Resource {
+    /// The bytes of a storage or uniform buffer, little-endian.
+    Buffer(Vec<u8>),
+    Texture(Texture),
+}
+
+/// The resources bound to group 0, by binding number.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Bindings(BTreeMap<u32, Resource>);
+
+impl Bindings {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_buffer(mut self, binding: u32, bytes: Vec<u8>) -> Self {
+        self.0.insert(binding, Resource::Buffer(bytes));
+        self
+    }
+
+    pub fn with_u32s(self, binding: u32, values: &[u32]) -> Self {
+        self.with_buffer(
+            binding,
+            values.iter().flat_map(|v| v.to_le_bytes()).collect(),
+        )
+    }
+
+    pub fn with_f32s(self, binding: u32, values: &[f32]) -> Self {
+        self.with_buffer(
+            binding,
+            values.iter().flat_map(|v| v.to_le_bytes()).collect(),
+        )
+    }
+
+    pub fn with_texture(mut self, binding: u32, texture: Texture) -> Self {
+        self.0.insert(binding, Resource::Texture(texture));
+        self
+    }
+
+    pub fn buffer(&self, binding: u32) -> Option<&[u8]> {
+        match self.0.get(&binding)? {
+            Resource::Buffer(bytes) => Some(bytes),
+            Resource::Texture(_) => None,
+        }
+    }
+
+    pub fn u32s(&self, binding: u32) -> Option<Vec<u32>> {
+        let words = self.buffer(binding)?.chunks_exact(4);
+        Some(
+            words
+                .map(|word| u32::from_le_bytes(word.try_into().unwrap()))
+                .collect(),
+        )
+    }
+
+    pub fn f32s(&self, binding: u32) -> Option<Vec<f32>> {
+        let words = self.buffer(binding)?.chunks_exact(4);
+        Some(
+            words
+                .map(|word| f32::from_le_bytes(word.try_into().unwrap()))
+                .collect(),
+        )
+    }
+
+    pub fn texture(&self, binding: u32) -> Option<&Texture> {
+        match self.0.get(&binding)? {
+            Resource::Texture(texture) => Some(texture),
+            Resource::Buffer(_) => None,
+        }
+    }
+}
+
+/// The type of what a buffer pointer points at. Vector components have no
+/// type handle of their own.
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Layout {
+    Type(Handle<Type>),
+    Scalar(ScalarKind),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Pointer {
+    /// Into a bound buffer, at a byte offset.
+    Buffer {
+        binding: u32,
+        offset: usize,
+        layout: Layout,
+    },
+    /// Into a local variable of some active call, by component path.
+    Local { slot: usize, path: Vec<usize> },
+    /// Into a private or workgroup global, by component path.
+    Global {
+        var: Handle<GlobalVariable>,
+        path: Vec<usize>,
+    },
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Value {
+    Bool(bool),
+    I32(i32),
+    U32(u32),
+    F32(f32),
+    /// A vector, array, matrix or struct.
+    Composite(Vec<Value>),
+    Pointer(Pointer),
+    /// The binding of a storage texture.
+    Texture(u32),
+}
+
+impl Value {
+    fn uvec(components: [u32; 3]) -> Value {
+        Value::Composite(components.map(Value::U32).to_vec())
+    }
+
+    fn zero(kind: ScalarKind) -> Value {
+        Value::from_bits(kind, 0)
+    }
+
+    fn from_bits(kind: ScalarKind, bits: u32) -> Value {
+        match kind {
+            ScalarKind::Sint => Value::I32(bits as i32),
+            ScalarKind::Uint => Value::U32(bits),
+            ScalarKind::Float => Value::F32(f32::from_bits(bits)),
+            ScalarKind::Bool => Value::Bool(bits != 0),
+        }
+    }
+
+    fn to_bits(&self) -> Result<u32> {
+        Ok(match *self {
+            Value::Bool(b) => b as u32,
+            Value::I32(v) => v as u32,
+            Value::U32(v) => v,
+            Value::F32(v) => v.to_bits(),
+            ref other => bail!("{:?} is not a scalar", other),
+        })
+    }
+
+    fn as_bool(&self) -> Result<bool> {
+        match *self {
+            Value::Bool(b) => Ok(b),
+            ref other => bail!("expected a bool, found {:?}", other),
+        }
+    }
+
+    fn as_f32(&self) -> Result<f32> {
+        match *self {
+            Value::F32(v) => Ok(v),
+            ref other => bail!("expected an f32, found {:?}", other),
+        }
+    }
+
+    /// An index or coordinate; negative values are out of bounds anyway.
+    fn as_int(&self) -> Result<i64> {
+        match *self {
+            Value::I32(v) => Ok(v as i64),
+            Value::U32(v) => Ok(v as i64),
+            ref other => bail!("expected an integer, found {:?}", other),
+        }
+    }
+
+    fn into_components(self) -> Result<Vec<Value>> {
+        match self {
+            Value::Composite(items) => Ok(items),
+            other => bail!("expected a vector, found {:?}", other),
+        }
+    }
+
+    fn into_pointer(self) -> Result<Pointer> {
+        match self {
+            Value::Pointer(pointer) => Ok(pointer),
+            other => bail!("expected a pointer, found {:?}", other),
+        }
+    }
+}
+
+/// Applies `f` to each scalar of `value`.
+fn map1(value: Value, f: &impl Fn(Value) -> Result<Value>) -> Result<Value> {
+    match value {
+        Value::Composite(items) => Ok(Value::Composite(
+            items
+                .into_iter()
+                .map(|item| map1(item, f))
+                .collect::<Result<_>>()?,
+        )),
+        scalar => f(scalar),
+    }
+}
+
+/// Applies `f` to matching scalars of `a` and `b`, repeating a scalar
+/// against every component of a vector.
+fn map2(a: Value, b: Value, f: &impl Fn(Value, Value) -> Result<Value>) -> Result<Value> {
+    match (a, b) {
+        (Value::Composite(xs), Value::Composite(ys)) if xs.len() == ys.len() => {
+            let items = xs.into_iter().zip(ys).map(|(x, y)| map2(x, y, f));
+            Ok(Value::Composite(items.collect::<Result<_>>()?))
+        }
+        (Value::Composite(_), Value::Composite(_)) => bail!("mismatched vector sizes"),
+        (Value::Composite(xs), y) => {
+            let items = xs.into_iter().map(|x| map2(x, y.clone(), f));
+            Ok(Value::Composite(items.collect::<Result<_>>()?))
+        }
+        (x, Value::Composite(ys)) => {
+            let items = ys.into_iter().map(|y| map2(x.clone(), y, f));
+            Ok(Value::Composite(items.collect::<Result<_>>()?))
+        }
+        (x, y) => f(x, y),
+    }
+}
+
+fn map3(
+    a: Value,
+    b: Value,
+    c: Value,
+    f: &impl Fn(Value, Value, Value) -> Result<Value>,
+) -> Result<Value> {
+    // Pair up the first two, then bring in the third.
+    let pairs = map2(a, b, &|x, y| Ok(Value::Composite(vec![x, y])))?;
+    map2(pairs, c, &|pair, z| match pair {
+        Value::Composite(mut xy) if xy.len() == 2 => {
+            let y = xy.pop().unwrap();
+            f(xy.pop().unwrap(), y, z)
+        }
+        _ => unreachable!(),
+    })
+    .or_else(|_| bail!("mismatched vector sizes"))
+}
+
+fn compare<T: PartialOrd>(op: BinaryOperator, x: T, y: T) -> Option<bool> {
+    Some(match op {
+        BinaryOperator::Equal => x == y,
+        BinaryOperator::NotEqual => x != y,
+        BinaryOperator::Less => x < y,
+        BinaryOperator::LessEqual => x <= y,
+        BinaryOperator::Greater => x > y,
+        BinaryOperator::GreaterEqual => x >= y,
+        _ => return None,
+    })
+}
+
+fn scalar_binary(op: BinaryOperator, a: Value, b: Value) -> Result<Value> {
+    apply(op, &a, &b).ok_or_else(|| anyhow!("can't apply {:?} to {:?} and {:?}", op, a, b))
+}
+
+fn apply(op: BinaryOperator, a: &Value, b: &Value) -> Option<Value> {
+    use BinaryOperator::*;
+    use Value::*;
+
+    Some(match (a, b) {
+        (I32(x), I32(y)) => {
+            let (x, y) = (*x, *y);
+            if let Some(result) = compare(op, x, y) {
+                return Some(Bool(result));
+            }
+            match op {
+                Add => I32(x.wrapping_add(y)),
+                Subtract => I32(x.wrapping_sub(y)),
+                Multiply => I32(x.wrapping_mul(y)),
+                // Division by zero and overflow give `x` and remainder zero.
+                Divide => I32(x.checked_div(y).unwrap_or(x)),
+                Modulo => I32(x.checked_rem(y).unwrap_or(0)),
+                And => I32(x & y),
+                InclusiveOr => I32(x | y),
+                ExclusiveOr => I32(x ^ y),
+                _ => return None,
+            }
+        }
+        (U32(x), U32(y)) => {
+            let (x, y) = (*x, *y);
+            if let Some(result) = compare(op, x, y) {
+                return Some(Bool(result));
+            }
+            match op {
+                Add => U32(x.wrapping_add(y)),
+                Subtract => U32(x.wrapping_sub(y)),
+                Multiply => U32(x.wrapping_mul(y)),
+                Divide => U32(x.checked_div(y).unwrap_or(x)),
+                Modulo => U32(x.checked_rem(y).unwrap_or(0)),
+                And => U32(x & y),
+                InclusiveOr => U32(x | y),
+                ExclusiveOr => U32(x ^ y),
+                ShiftLeft => U32(x.wrapping_shl(y)),
+                ShiftRight => U32(x.wrapping_shr(y)),
+                _ => return None,
+            }
+        }
+        (I32(x), U32(y)) => match op {
+            ShiftLeft => I32(x.wrapping_shl(*y)),
+            ShiftRight => I32(x.wrapping_shr(*y)),
+            _ => return None,
+        },
+        (F32(x), F32(y)) => {
+            let (x, y) = (*x, *y);
+            if let Some(result) = compare(op, x, y) {
+                return Some(Bool(result));
+            }
+            match op {
+                Add => F32(x + y),
+                Subtract => F32(x - y),
+                Multiply => F32(x * y),
+                Divide => F32(x / y),
+                Modulo => F32(x % y),
+                _ => return None,
+            }
+        }
+        (Bool(x), Bool(y)) => {
+            let (x, y) = (*x, *y);
+            match op {
+                Equal => Bool(x == y),
+                NotEqual => Bool(x != y),
+                LogicalAnd | And => Bool(x && y),
+                LogicalOr | InclusiveOr => Bool(x || y),
+                _ => return None,
+            }
+        }
+        _ => return None,
+    })
+}
+
+fn cast(value: Value, kind: ScalarKind, convert: Option<u8>) -> Result<Value> {
+    match convert {
+        None => map1(value, &|v| Ok(Value::from_bits(kind, v.to_bits()?))),
+        Some(1 | 4) => map1(value, &|v| {
+            Ok(match (v, kind) {
+                (Value::Bool(b), ScalarKind::Float) => Value::F32(b as u32 as f32),
+                (Value::Bool(b), _) => Value::from_bits(kind, b as u32),
+                (Value::I32(x), ScalarKind::Float) => Value::F32(x as f32),
+                (Value::U32(x), ScalarKind::Float) => Value::F32(x as f32),
+                (Value::I32(x), _) => Value::from_bits(kind, x as u32),
+                (Value::U32(x), _) => Value::from_bits(kind, x),
+                (Value::F32(x), ScalarKind::Sint) => Value::I32(x as i32),
+                (Value::F32(x), ScalarKind::Uint) => Value::U32(x as u32),
+                (Value::F32(x), ScalarKind::Float) => Value::F32(x),
+                (Value::F32(x), ScalarKind::Bool) => Value::Bool(x != 0.0),
+                (other, _) => bail!("can't convert {:?}", other),
+            })
+        }),
+        Some(width) => bail!("{}-byte values are not supported", width),
+    }
+}
+
+fn float_function(fun: MathFunction) -> Option<fn(f32) -> f32> {
+    use MathFunction as M;
+    let f: fn(f32) -> f32 = match fun {
+        M::Cos => f32::cos,
+        M::Cosh => f32::cosh,
+        M::Sin => f32::sin,
+        M::Sinh => f32::sinh,
+        M::Tan => f32::tan,
+        M::Tanh => f32::tanh,
+        M::Acos => f32::acos,
+        M::Asin => f32::asin,
+        M::Atan => f32::atan,
+        M::Asinh => f32::asinh,
+        M::Acosh => f32::acosh,
+        M::Atanh => f32::atanh,
+        M::Radians => f32::to_radians,
+        M::Degrees => f32::to_degrees,
+        M::Ceil => f32::ceil,
+        M::Floor => f32::floor,
+        M::Round => f32::round_ties_even,
+        M::Fract => |x| x - x.floor(),
+        M::Trunc => f32::trunc,
+        M::Exp => f32::exp,
+        M::Exp2 => f32::exp2,
+        M::Log => f32::ln,
+        M::Log2 => f32::log2,
+        M::Sqrt => f32::sqrt,
+        M::InverseSqrt => |x| 1.0 / x.sqrt(),
+        M::Saturate => |x| x.clamp(0.0, 1.0),
+        _ => return None,
+    };
+    Some(f)
+}
+
+fn dot(a: Value, b: Value) -> Result<Value> {
+    let products = map2(a, b, &|x, y| scalar_binary(BinaryOperator::Multiply, x, y))?;
+    let mut components = products.into_components()?.into_iter();
+    let first = components.next().ok_or_else(|| anyhow!("empty vector"))?;
+    components.try_fold(first, |sum, x| scalar_binary(BinaryOperator::Add, sum, x))
+}
+
+fn math(fun: MathFunction, args: Vec<Value>) -> Result<Value> {
+    use MathFunction as M;
+    use Value::*;
+
+    let mut args = args.into_iter();
+    let mut arg = || {
+        args.next()
+            .ok_or_else(|| anyhow!("{:?} is missing an argument", fun))
+    };
+    if let Some(f) = float_function(fun) {
+        return map1(arg()?, &|x| Ok(F32(f(x.as_f32()?))));
+    }
+    match fun {
+        M::Abs => map1(arg()?, &|x| {
+            Ok(match x {
+                I32(v) => I32(v.wrapping_abs()),
+                F32(v) => F32(v.abs()),
+                other => other,
+            })
+        }),
+        M::Sign => map1(arg()?, &|x| {
+            Ok(match x {
+                I32(v) => I32(v.signum()),
+                F32(v) => F32(if v == 0.0 { 0.0 } else { v.signum() }),
+                other => bail!("sign of {:?}", other),
+            })
+        }),
+        M::Min | M::Max => {
+            let (a, b) = (arg()?, arg()?);
+            map2(a, b, &|x, y| {
+                let less = scalar_binary(BinaryOperator::Less, x.clone(), y.clone())?.as_bool()?;
+                Ok(if less == (fun == M::Min) { x } else { y })
+            })
+        }
+        M::Clamp => {
+            let (x, low, high) = (arg()?, arg()?, arg()?);
+            let raised = math(M::Max, vec![x, low])?;
+            math(M::Min, vec![raised, high])
+        }
+        M::Pow | M::Atan2 | M::Step => {
+            let (a, b) = (arg()?, arg()?);
+            map2(a, b, &|x, y| {
+                let (x, y) = (x.as_f32()?, y.as_f32()?);
+                Ok(F32(match fun {
+                    M::Pow => x.powf(y),
+                    M::Atan2 => x.atan2(y),
+                    _ => (y >= x) as u32 as f32,
+                }))
+            })
+        }
+        M::Mix | M::Fma | M::SmoothStep => {
+            let (a, b, c) = (arg()?, arg()?, arg()?);
+            map3(a, b, c, &|x, y, z| {
+                let (x, y, z) = (x.as_f32()?, y.as_f32()?, z.as_f32()?);
+                Ok(F32(match fun {
+                    M::Mix => x * (1.0 - z) + y * z,
+                    M::Fma => x.mul_add(y, z),
+                    _ => {
+                        let t = ((z - x) / (y - x)).clamp(0.0, 1.0);
+                        t * t * (3.0 - 2.0 * t)
+                    }
+                }))
+            })
+        }
+        M::Dot => dot(arg()?, arg()?),
+        M::Length => {
+            let v = arg()?;
+            Ok(F32(dot(v.clone(), v)?.as_f32()?.sqrt()))
+        }
+        M::Distance => {
+            let difference = map2(arg()?, arg()?, &|x, y| {
+                scalar_binary(BinaryOperator::Subtract, x, y)
+            })?;
+            math(M::Length, vec![difference])
+        }
+        M::Normalize => {
+            let v = arg()?;
+            let length = math(M::Length, vec![v.clone()])?;
+            map2(v, length, &|x, y| {
+                scalar_binary(BinaryOperator::Divide, x, y)
+            })
+        }
+        M::CountOneBits | M::ReverseBits | M::FindLsb | M::FindMsb => map1(arg()?, &|x| {
+            let bits = x.to_bits()?;
+            let result = match fun {
+                M::CountOneBits => bits.count_ones(),
+                M::ReverseBits => bits.reverse_bits(),
+                M::FindLsb if bits == 0 => u32::MAX,
+                M::FindLsb => bits.trailing_zeros(),
+                _ => match x {
+                    // The highest bit that differs from the sign bit.
+                    I32(v) if v < 0 => (!bits).checked_ilog2().unwrap_or(u32::MAX),
+                    _ => bits.checked_ilog2().unwrap_or(u32::MAX),
+                },
+            };
+            Ok(match x {
+                I32(_) => I32(result as i32),
+                _ => U32(result),
+            })
+        }),
+        other => bail!("unsupported math function {:?}", other),
+    }
+}
+
+/// How a block finished.
+enum Flow {
+    Next,
+    Break,
+    Continue,
+    Return(Option<Value>),
+}
+
+/// The expression values of one call, filled in as they're evaluated.
+struct Frame<'a> {
+    function: &'a Function,
+    args: Vec<Value>,
+    values: Vec<Option<Value>>,
+    /// Where this call's local variables start in [`Machine::locals`].
+    base: usize,
+}
+
+struct Machine<'a> {
+    module: &'a Module,
+    bindings: &'a mut Bindings,
+    /// Private and workgroup globals.
+    globals: HashMap<Handle<GlobalVariable>, Value>,
+    /// The local variables of every active call, innermost last.
+    locals: Vec<Value>,
+    steps: u64,
+    step_limit: u64,
+}
+
+impl<'a> Machine<'a> {
+    fn reset_globals(&mut self, space: AddressSpace) -> Result<()> {
+        for (handle, global) in self.module.global_variables.iter() {
+            if global.space == space {
+                let value = match global.init {
+                    Some(init) => self.constant(init)?,
+                    None => self.zero(global.ty)?,
+                };
+                self.globals.insert(handle, value);
+            }
+        }
+        Ok(())
+    }
+
+    fn constant(&self, handle: Handle<naga::Constant>) -> Result<Value> {
+        Ok(match &self.module.constants[handle].inner {
+            ConstantInner::Scalar { width, value } => match *value {
+                ScalarValue::Bool(b) => Value::Bool(b),
+                _ if *width != 4 => bail!("{}-byte constants are not supported", width),
+                ScalarValue::Sint(v) => Value::I32(v as i32),
+                ScalarValue::Uint(v) => Value::U32(v as u32),
+                ScalarValue::Float(v) => Value::F32(v as f32),
+            },
+            ConstantInner::Composite { components, .. } => Value::Composite(
+                components
+                    .iter()
+                    .map(|c| self.constant(*c))
+                    .collect::<Result<_>>()?,
+            ),
+        })
+    }
+
+    fn array_len(&self, size: ArraySize) -> Result<usize> {
+        match size {
+            ArraySize::Constant(handle) => Ok(self.constant(handle)?.as_int()? as usize),
+            ArraySize::Dynamic => bail!("runtime-sized arrays only live in buffers"),
+        }
+    }
+
+    fn zero(&self, ty: Handle<Type>) -> Result<Value> {
+        Ok(match &self.module.types[ty].inner {
+            TypeInner::Scalar { kind, .. } | TypeInner::Atomic { kind, .. } => Value::zero(*kind),
+            TypeInner::Vector { size, kind, .. } => {
+                Value::Composite(vec![Value::zero(*kind); *size as usize])
+            }
+            TypeInner::Matrix { columns, rows, .. } => {
+                Value::Composite(vec![
+                    Value::Composite(vec![Value::F32(0.0); *rows as usize]);
+                    *columns as usize
+                ])
+            }
+            TypeInner::Array { base, size, .. } => {
+                Value::Composite(vec![self.zero(*base)?; self.array_len(*size)?])
+            }
+            TypeInner::Struct { members, .. } => Value::Composite(
+                members
+                    .iter()
+                    .map(|member| self.zero(member.ty))
+                    .collect::<Result<_>>()?,
+            ),
+            other => bail!("can't make a {:?}", other),
+        })
+    }
+
+    fn buffer(&self, binding: u32) -> Result<&[u8]> {
+        self.bindings
+            .buffer(binding)
+            .ok_or_else(|| anyhow!("no buffer at binding {}", binding))
+    }
+
+    fn texture(&mut self, binding: u32) -> Result<&mut Texture> {
+        match self.bindings.0.get_mut(&binding) {
+            Some(Resource::Texture(texture)) => Ok(texture),
+            _ => bail!("no texture at binding {}", binding),
+        }
+    }
+
+    fn read(&self, bytes: &[u8], offset: usize, layout: Layout) -> Result<Value> {
+        let word = |offset: usize| {
+            let word = bytes.get(offset..offset.saturating_add(4));
+            word.map_or(0, |word| u32::from_le_bytes(word.try_into().unwrap()))
+        };
+        let ty = match layout {
+            Layout::Scalar(kind) => return Ok(Value::from_bits(kind, word(offset))),
+            Layout::Type(ty) => ty,
+        };
+        Ok(match &self.module.types[ty].inner {
+            TypeInner::Scalar { kind, width: 4 } | TypeInner::Atomic { kind, width: 4 } => {
+                Value::from_bits(*kind, word(offset))
+            }
+            TypeInner::Vector {
+                size,
+                kind,
+                width: 4,
+            } => Value::Composite(
+                (0..*size as usize)
+                    .map(|i| Value::from_bits(*kind, word(offset + 4 * i)))
+                    .collect(),
+            ),
+            TypeInner::Array { base, size, stride } => Value::Composite(
+                (0..self.array_len(*size)?)
+                    .map(|i| self.read(bytes, offset + i * *stride as usize, Layout::Type(*base)))
+                    .collect::<Result<_>>()?,
+            ),
+            TypeInner::Struct { members, .. } => Value::Composite(
+                members
+                    .iter()
+                    .map(|m| self.read(bytes, offset + m.offset as usize, Layout::Type(m.ty)))
+                    .collect::<Result<_>>()?,
+            ),
+            other => bail!("can't load a {:?} from a buffer", other),
+        })
+    }
+
+    /// Writes `value` into `bytes` laid out as `layout`. Takes the module
+    /// rather than `self` so the buffer can be borrowed from the bindings.
+    fn write(
+        module: &Module,
+        bytes: &mut [u8],
+        offset: usize,
+        layout: Layout,
+        value: &Value,
+    ) -> Result<()> {
+        let ty = match layout {
+            Layout::Scalar(_) => None,
+            Layout::Type(ty) => Some(&module.types[ty].inner),
+        };
+        match (ty, value) {
+            (
+                None | Some(TypeInner::Scalar { .. } | TypeInner::Atomic { .. }),
+                Value::Bool(_) | Value::I32(_) | Value::U32(_) | Value::F32(_),
+            ) => {
+                if let Some(word) = bytes.get_mut(offset..offset.saturating_add(4)) {
+                    word.copy_from_slice(&value.to_bits()?.to_le_bytes());
+                }
+            }
+            (Some(TypeInner::Vector { kind, .. }), Value::Composite(items)) => {
+                for (i, item) in items.iter().enumerate() {
+                    Self::write(module, bytes, offset + 4 * i, Layout::Scalar(*kind), item)?;
+                }
+            }
+            (Some(TypeInner::Array { base, stride, .. }), Value::Composite(items)) => {
+                for (i, item) in items.iter().enumerate() {
+                    let at = offset + i * *stride as usize;
+                    Self::write(module, bytes, at, Layout::Type(*base), item)?;
+                }
+            }
+            (Some(TypeInner::Struct { members, .. }), Value::Composite(items)) => {
+                for (member, item) in members.iter().zip(items) {
+                    let at = offset + member.offset as usize;
+                    Self::write(module, bytes, at, Layout::Type(member.ty), item)?;
+                }
+            }
+            (ty, value) => bail!("can't store {:?} as {:?}", value, ty),
+        }
+        Ok(())
+    }
+
+    fn load(&self, pointer: &Pointer) -> Result<Value> {
+        match pointer {
+            Pointer::Buffer {
+                binding,
+                offset,
+                layout,
+            } => self.read(self.buffer(*binding)?, *offset, *layout),
+            Pointer::Local { slot, path } => follow(&self.locals[*slot], path).cloned(),
+            Pointer::Global { var, path } => follow(&self.globals[var], path).cloned(),
+        }
+    }
+
+    fn store(&mut self, pointer: &Pointer, value: Value) -> Result<()> {
+        let place = match pointer {
+            Pointer::Buffer {
+                binding,
+                offset,
+                layout,
+            } => {
+                let bytes = match self.bindings.0.get_mut(binding) {
+                    Some(Resource::Buffer(bytes)) => bytes,
+                    _ => bail!("no buffer at binding {}", binding),
+                };
+                return Self::write(self.module, bytes, *offset, *layout, &value);
+            }
+            Pointer::Local { slot, path } => follow_mut(&mut self.locals[*slot], path)?,
+            Pointer::Global { var, path } => follow_mut(self.globals.get_mut(var).unwrap(), path)?,
+        };
+        *place = value;
+        Ok(())
+    }
+
+    /// A pointer to component `index` of what `pointer` points at.
+    fn element(&self, pointer: Pointer, index: usize) -> Result<Pointer> {
+        Ok(match pointer {
+            Pointer::Buffer {
+                binding,
+                offset,
+                layout: Layout::Type(ty),
+            } => {
+                let (step, layout) = match &self.module.types[ty].inner {
+                    TypeInner::Vector { kind, width, .. } => {
+                        (index.saturating_mul(*width as usize), Layout::Scalar(*kind))
+                    }
+                    TypeInner::Array { base, stride, .. } => {
+                        (index.saturating_mul(*stride as usize), Layout::Type(*base))
+                    }
+                    TypeInner::Struct { members, .. } => {
+                        let member = &members[index];
+                        (member.offset as usize, Layout::Type(member.ty))
+                    }
+                    other => bail!("can't index into {:?}", other),
+                };
+                Pointer::Buffer {
+                    binding,
+                    offset: offset.saturating_add(step),
+                    layout,
+                }
+            }
+            Pointer::Buffer { .. } => bail!("can't index into a scalar"),
+            Pointer::Local { slot, mut path } => {
+                path.push(index);
+                Pointer::Local { slot, path }
+            }
+            Pointer::Global { var, mut path } => {
+                path.push(index);
+                Pointer::Global { var, path }
+            }
+        })
+    }
+
+    fn access(&self, base: Value, index: usize) -> Result<Value> {
+        match base {
+            Value::Pointer(pointer) => Ok(Value::Pointer(self.element(pointer, index)?)),
+            Value::Composite(mut items) if index < items.len() => Ok(items.swap_remove(index)),
+            Value::Composite(items) => bail!("index {} out of bounds for {}", index, items.len()),
+            other => bail!("can't index into {:?}", other),
+        }
+    }
+
+    fn global(&self, handle: Handle<GlobalVariable>) -> Result<Value> {
+        let global = &self.module.global_variables[handle];
+        Ok(match global.space {
+            AddressSpace::Storage { .. } | AddressSpace::Uniform => {
+                Value::Pointer(Pointer::Buffer {
+                    binding: binding_of(global)?,
+                    offset: 0,
+                    layout: Layout::Type(global.ty),
+                })
+            }
+            AddressSpace::Handle => Value::Texture(binding_of(global)?),
+            AddressSpace::Private | AddressSpace::WorkGroup => Value::Pointer(Pointer::Global {
+                var: handle,
+                path: Vec::new(),
+            }),
+            other => bail!("unsupported address space {:?}", other),
+        })
+    }
+
+    fn coordinates(value: Value) -> Result<(i64, i64)> {
+        match value.into_components()?.as_slice() {
+            [x, y] => Ok((x.as_int()?, y.as_int()?)),
+            other => bail!("expected 2D coordinates, found {:?}", other),
+        }
+    }
+
+    fn image_load(&mut self, image: Value, coordinate: Value) -> Result<Value> {
+        let (x, y) = Self::coordinates(coordinate)?;
+        let binding = match image {
+            Value::Texture(binding) => binding,
+            other => bail!("expected a texture, found {:?}", other),
+        };
+        let texture = self.texture(binding)?;
+        let texel = texture
+            .index(x, y)
+            .map_or([0; 4], |idx| texture.texels[idx]);
+        Ok(Value::Composite(
+            texel
+                .iter()
+                .map(|c| Value::F32(*c as f32 / 255.0))
+                .collect(),
+        ))
+    }
+
+    fn image_store(&mut self, image: Value, coordinate: Value, value: Value) -> Result<()> {
+        let (x, y) = Self::coordinates(coordinate)?;
+        let binding = match image {
+            Value::Texture(binding) => binding,
+            other => bail!("expected a texture, found {:?}", other),
+        };
+        let mut texel = [0; 4];
+        for (c, v) in texel.iter_mut().zip(value.into_components()?) {
+            *c = (v.as_f32()?.clamp(0.0, 1.0) * 255.0).round() as u8;
+        }
+        let texture = self.texture(binding)?;
+        if let Some(idx) = texture.index(x, y) {
+            texture.texels[idx] = texel;
+        }
+        Ok(())
+    }
+
+    fn eval(&mut self, frame: &mut Frame<'a>, expr: Handle<Expression>) -> Result<Value> {
+        if let Some(value) = &frame.values[expr.index()] {
+            return Ok(value.clone());
+        }
+        let value = self.compute(frame, expr)?;
+        frame.values[expr.index()] = Some(value.clone());
+        Ok(value)
+    }
+
+    fn compute(&mut self, frame: &mut Frame<'a>, expr: Handle<Expression>) -> Result<Value> {
+        Ok(match &frame.function.expressions[expr] {
+            Expression::Access { base, index } => {
+                let index = self.eval(frame, *index)?.as_int()?;
+                let base = self.eval(frame, *base)?;
+                self.access(base, usize::try_from(index).unwrap_or(usize::MAX))?
+            }
+            Expression::AccessIndex { base, index } => {
+                let base = self.eval(frame, *base)?;
+                self.access(base, *index as usize)?
+            }
+            Expression::Constant(handle) => self.constant(*handle)?,
+            Expression::Splat { size, value } => {
+                Value::Composite(vec![self.eval(frame, *value)?; *size as usize])
+            }
+            Expression::Swizzle {
+                size,
+                vector,
+                pattern,
+            } => {
+                let components = self.eval(frame, *vector)?.into_components()?;
+                Value::Composite(
+                    pattern[..*size as usize]
+                        .iter()
+                        .map(|c| components[*c as usize].clone())
+                        .collect(),
+                )
+            }
+            Expression::Compose { ty, components } => {
+                let mut values = Vec::new();
+                for component in components {
+                    match (&self.module.types[*ty].inner, self.eval(frame, *component)?) {
+                        // Vectors can be built from smaller vectors.
+                        (TypeInner::Vector { .. }, Value::Composite(items)) => values.extend(items),
+                        (_, value) => values.push(value),
+                    }
+                }
+                Value::Composite(values)
+            }
+            Expression::FunctionArgument(idx) => frame.args[*idx as usize].clone(),
+            Expression::GlobalVariable(handle) => self.global(*handle)?,
+            Expression::LocalVariable(handle) => Value::Pointer(Pointer::Local {
+                slot: frame.base + handle.index(),
+                path: Vec::new(),
+            }),
+            Expression::Load { pointer } => {
+                let pointer = self.eval(frame, *pointer)?.into_pointer()?;
+                self.load(&pointer)?
+            }
+            Expression::ImageLoad {
+                image,
+                coordinate,
+                array_index: None,
+                ..
+            } => {
+                let image = self.eval(frame, *image)?;
+                let coordinate = self.eval(frame, *coordinate)?;
+                self.image_load(image, coordinate)?
+            }
+            Expression::ImageQuery {
+                image,
+                query: ImageQuery::Size { .. },
+            } => match self.eval(frame, *image)? {
+                Value::Texture(binding) => {
+                    let texture = self.texture(binding)?;
+                    Value::Composite(vec![Value::U32(texture.width), Value::U32(texture.height)])
+                }
+                other => bail!("expected a texture, found {:?}", other),
+            },
+            Expression::Unary { op, expr } => {
+                let op = *op;
+                map1(self.eval(frame, *expr)?, &|x| {
+                    Ok(match (op, x) {
+                        (UnaryOperator::Negate, Value::I32(v)) => Value::I32(v.wrapping_neg()),
+                        (UnaryOperator::Negate, Value::F32(v)) => Value::F32(-v),
+                        (UnaryOperator::Not, Value::Bool(b)) => Value::Bool(!b),
+                        (UnaryOperator::Not, Value::I32(v)) => Value::I32(!v),
+                        (UnaryOperator::Not, Value::U32(v)) => Value::U32(!v),
+                        (op, x) => bail!("can't apply {:?} to {:?}", op, x),
+                    })
+                })?
+            }
+            Expression::Binary { op, left, right } => {
+                let op = *op;
+                let (left, right) = (self.eval(frame, *left)?, self.eval(frame, *right)?);
+                let is_matrix = |v: &Value| matches!(v, Value::Composite(items) if matches!(items.first(), Some(Value::Composite(_))));
+                if op == BinaryOperator::Multiply && (is_matrix(&left) || is_matrix(&right)) {
+                    bail!("matrix multiplication is not supported");
+                }
+                map2(left, right, &|x, y| scalar_binary(op, x, y))?
+            }
+            Expression::Select {
+                condition,
+                accept,
+                reject,
+            } => {
+                let condition = self.eval(frame, *condition)?;
+                let (accept, reject) = (self.eval(frame, *accept)?, self.eval(frame, *reject)?);
+                match condition {
+                    Value::Bool(true) => accept,
+                    Value::Bool(false) => reject,
+                    mask => map3(mask, accept, reject, &|c, a, r| {
+                        Ok(if c.as_bool()? { a } else { r })
+                    })?,
+                }
+            }
+            Expression::Relational { fun, argument } => {
+                let argument = self.eval(frame, *argument)?;
+                match fun {
+                    RelationalFunction::All | RelationalFunction::Any => {
+                        let mut bits = match argument {
+                            Value::Composite(items) => items,
+                            scalar => vec![scalar],
+                        }
+                        .into_iter()
+                        .map(|b| b.as_bool());
+                        Value::Bool(if *fun == RelationalFunction::All {
+                            bits.try_fold(true, |all, b| Ok::<_, anyhow::Error>(all && b?))?
+                        } else {
+                            bits.try_fold(false, |any, b| Ok::<_, anyhow::Error>(any || b?))?
+                        })
+                    }
+                    _ => {
+                        let fun = *fun;
+                        map1(argument, &|x| {
+                            let x = x.as_f32()?;
+                            Ok(Value::Bool(match fun {
+                                RelationalFunction::IsNan => x.is_nan(),
+                                RelationalFunction::IsInf => x.is_infinite(),
+                                RelationalFunction::IsFinite => x.is_finite(),
+                                _ => x.is_normal(),
+                            }))
+                        })?
+                    }
+                }
+            }
+            Expression::Math {
+                fun,
+                arg,
+                arg1,
+                arg2,
+                arg3,
+            } => {
+                let mut args = vec![self.eval(frame, *arg)?];
+                for extra in [arg1, arg2, arg3].into_iter().flatten() {
+                    args.push(self.eval(frame, *extra)?);
+                }
+                math(*fun, args)?
+            }
+            Expression::As {
+                expr,
+                kind,
+                convert,
+            } => cast(self.eval(frame, *expr)?, *kind, *convert)?,
+            Expression::ArrayLength(pointer) => match self.eval(frame, *pointer)?.into_pointer()? {
+                Pointer::Buffer {
+                    binding,
+                    offset,
+                    layout: Layout::Type(ty),
+                } => match self.module.types[ty].inner {
+                    TypeInner::Array { stride, .. } => {
+                        let bytes = self.buffer(binding)?.len().saturating_sub(offset);
+                        Value::U32((bytes / stride as usize) as u32)
+                    }
+                    ref other => bail!("arrayLength of {:?}", other),
+                },
+                other => bail!("arrayLength of {:?}", other),
+            },
+            Expression::CallResult(_) => bail!("call result used before the call"),
+            other => bail!("unsupported expression {:?}", other),
+        })
+    }
+
+    fn exec(&mut self, frame: &mut Frame<'a>, block: &'a Block) -> Result<Flow> {
+        for statement in block {
+            let flow = match statement {
+                Statement::Emit(range) => {
+                    for expr in range.clone() {
+                        let value = self.compute(frame, expr)?;
+                        frame.values[expr.index()] = Some(value);
+                    }
+                    Flow::Next
+                }
+                Statement::Block(block) => self.exec(frame, block)?,
+                Statement::If {
+                    condition,
+                    accept,
+                    reject,
+                } => {
+                    if self.eval(frame, *condition)?.as_bool()? {
+                        self.exec(frame, accept)?
+                    } else {
+                        self.exec(frame, reject)?
+                    }
+                }
+                Statement::Switch { selector, cases } => self.switch(frame, *selector, cases)?,
+                Statement::Loop {
+                    body,
+                    continuing,
+                    break_if,
+                } => self.run_loop(frame, body, continuing, *break_if)?,
+                Statement::Break => Flow::Break,
+                Statement::Continue => Flow::Continue,
+                Statement::Return { value } => {
+                    Flow::Return(value.map(|value| self.eval(frame, value)).transpose()?)
+                }
+                Statement::Barrier(_) => Flow::Next,
+                Statement::Store { pointer, value } => {
+                    let pointer = self.eval(frame, *pointer)?.into_pointer()?;
+                    let value = self.eval(frame, *value)?;
+                    self.store(&pointer, value)?;
+                    Flow::Next
+                }
+                Statement::ImageStore {
+                    image,
+                    coordinate,
+                    array_index: None,
+                    value,
+                } => {
+                    let image = self.eval(frame, *image)?;
+                    let coordinate = self.eval(frame, *coordinate)?;
+                    let value = self.eval(frame, *value)?;
+                    self.image_store(image, coordinate, value)?;
+                    Flow::Next
+                }
+                Statement::Call {
+                    function,
+                    arguments,
+                    result,
+                } => {
+                    let args = arguments
+                        .iter()
+                        .map(|arg| self.eval(frame, *arg))
+                        .collect::<Result<_>>()?;
+                    let value = self.call(&self.module.functions[*function], args)?;
+                    if let Some(result) = result {
+                        let value = value.ok_or_else(|| anyhow!("function returned nothing"))?;
+                        frame.values[result.index()] = Some(value);
+                    }
+                    Flow::Next
+                }
+                other => bail!("unsupported statement {:?}", other),
+            };
+            if !matches!(flow, Flow::Next) {
+                return Ok(flow);
+            }
+        }
+        Ok(Flow::Next)
+    }
+
+    fn switch(
+        &mut self,
+        frame: &mut Frame<'a>,
+        selector: Handle<Expression>,
+        cases: &'a [SwitchCase],
+    ) -> Result<Flow> {
+        // Case values of `u32` selectors are stored as `i32` bit patterns.
+        let selector = self.eval(frame, selector)?.to_bits()? as i32;
+        let start = cases
+            .iter()
+            .position(|case| matches!(case.value, SwitchValue::Integer(value) if value == selector))
+            .or_else(|| {
+                cases
+                    .iter()
+                    .position(|case| matches!(case.value, SwitchValue::Default))
+            });
+        for case in &cases[start.unwrap_or(cases.len())..] {
+            match self.exec(frame, &case.body)? {
+                Flow::Next if case.fall_through => continue,
+                Flow::Next | Flow::Break => return Ok(Flow::Next),
+                flow => return Ok(flow),
+            }
+        }
+        Ok(Flow::Next)
+    }
+
+    fn run_loop(
+        &mut self,
+        frame: &mut Frame<'a>,
+        body: &'a Block,
+        continuing: &'a Block,
+        break_if: Option<Handle<Expression>>,
+    ) -> Result<Flow> {
+        loop {
+            self.steps += 1;
+            if self.steps > self.step_limit {
+                bail!("gave up after {} loop iterations", self.step_limit);
+            }
+            match self.exec(frame, body)? {
+                Flow::Break => return Ok(Flow::Next),
+                flow @ Flow::Return(_) => return Ok(flow),
+                Flow::Next | Flow::Continue => {}
+            }
+            if let flow @ Flow::Return(_) = self.exec(frame, continuing)? {
+                return Ok(flow);
+            }
+            if let Some(condition) = break_if {
+                if self.eval(frame, condition)?.as_bool()? {
+                    return Ok(Flow::Next);
+                }
+            }
+        }
+    }
+
+    fn call(&mut self, function: &'a Function, args: Vec<Value>) -> Result<Option<Value>> {
+        let base = self.locals.len();
+        for (_, local) in function.local_variables.iter() {
+            let value = match local.init {
+                Some(init) => self.constant(init)?,
+                None => self.zero(local.ty)?,
+            };
+            self.locals.push(value);
+        }
+        let mut frame = Frame {
+            function,
+            args,
+            values: vec![None; function.expressions.len()],
+            base,
+        };
+        let flow = self.exec(&mut frame, &function.body);
+        self.locals.truncate(base);
+        match flow? {
+            Flow::Return(value) => Ok(value),
+            _ => Ok(None),
+        }
+    }
+}
+
+fn follow<'v>(mut value: &'v Value, path: &[usize]) -> Result<&'v Value> {
+    for &idx in path {
+        value = match value {
+            Value::Composite(items) => items
+                .get(idx)
+                .ok_or_else(|| anyhow!("index {} out of bounds for {}", idx, items.len()))?,
+            other => bail!("can't index into {:?}", other),
+        };
+    }
+    Ok(value)
+}
+
+fn follow_mut<'v>(mut value: &'v mut Value, path: &[usize]) -> Result<&'v mut Value> {
+    for &idx in path {
+        value = match value {
+            Value::Composite(items) => {
+                let len = items.len();
+                items
+                    .get_mut(idx)
+                    .ok_or_else(|| anyhow!("index {} out of bounds for {}", idx, len))?
+            }
+            other => bail!("can't index into {:?}", other),
+        };
+    }
+    Ok(value)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const COLLATZ: &str = "
+        @group(0) @binding(0) var<storage, read> starts: array<u32>;
+        @group(0) @binding(1) var<storage, read_write> steps: array<i32>;
+
+        fn collatz(start: u32) -> i32 {
+            var n = start;
+            var count = 0;
+            loop {
+                if n <= 1u { break; }
+                switch n % 2u {
+                    case 0u: { n = n / 2u; }
+                    default: { n = 3u * n + 1u; }
+                }
+                count += 1;
+            }
+            return count;
+        }
+
+        @compute @workgroup_size(4)
+        fn main(@builtin(global_invocation_id) id: vec3<u32>) {
+            if id.x >= arrayLength(&starts) { return; }
+            steps[id.x] = collatz(starts[id.x]);
+        }
+    ";
+
+    #[test]
+    fn loops_calls_and_buffers() {
+        let kernel = Kernel::parse(COLLATZ).unwrap();
+        assert_eq!(kernel.workgroup_size("main"), Some([4, 1, 1]));
+        let mut bindings = Bindings::new()
+            .with_u32s(0, &[1, 2, 3, 6, 7, 27])
+            .with_buffer(1, vec![0xff; 4 * 6]);
+        kernel.dispatch("main", [2, 1, 1], &mut bindings).unwrap();
+        assert_eq!(
+            bindings.u32s(1).unwrap(),
+            [0, 1, 7, 8, 16, 111].map(|steps: i32| steps as u32)
+        );
+
+        let err = kernel
+            .dispatch("main", [1, 1, 1], &mut Bindings::new().with_u32s(0, &[]))
+            .unwrap_err();
+        assert_eq!(err.to_string(), "nothing bound at binding 1 for steps");
+        assert!(kernel.dispatch("init", [1, 1, 1], &mut bindings).is_err());
+    }
+
+    #[test]
+    fn vector_math_and_textures() {
+        let kernel = Kernel::parse(
+            "
+            @group(0) @binding(0) var image: texture_storage_2d<rgba8unorm, read_write>;
+            @group(0) @binding(1) var<uniform> scale: vec2<f32>;
+            @group(0) @binding(2) var<storage, read_write> out: array<vec4<f32>>;
+
+            @compute @workgroup_size(2, 2)
+            fn main(@builtin(global_invocation_id) id: vec3<u32>) {
+                let at = vec2<i32>(id.xy);
+                let size = vec2<f32>(textureDimensions(image));
+                let uv = (vec2<f32>(id.xy) + 0.5) / size * scale;
+                let old = textureLoad(image, at);
+                textureStore(image, at, vec4<f32>(clamp(uv, vec2(0.0), vec2(1.0)), old.b, 1.0));
+                let index = id.y * 2u + id.x;
+                out[index] = vec4<f32>(dot(uv, uv), length(vec2(3.0, 4.0)), select(1.0, 2.0, all(uv < vec2(1.0))), f32(-7 / 2));
+            }
+            ",
+        )
+        .unwrap();
+        let mut texture = Texture::new(2, 2, [0, 0, 0, 0]);
+        texture.set(1, 1, [0, 0, 200, 0]);
+        let mut bindings = Bindings::new()
+            .with_texture(0, texture)
+            .with_f32s(1, &[1.0, 2.0])
+            .with_f32s(2, &[0.0; 16]);
+        kernel.dispatch("main", [1, 1, 1], &mut bindings).unwrap();
+
+        let texture = bindings.texture(0).unwrap();
+        assert_eq!(texture.get(0, 0), Some([64, 128, 0, 255]));
+        assert_eq!(texture.get(1, 1), Some([191, 255, 200, 255]));
+        let out = bindings.f32s(2).unwrap();
+        assert_eq!(out[..4], [0.3125, 5.0, 2.0, -3.0]);
+        assert_eq!(out[12..], [0.5625 + 2.25, 5.0, 1.0, -3.0]);
+        assert_eq!(texture.to_canvas().get(1, 0), Some(Rgb(191, 128, 0)));
+    }
+
+    #[test]
+    fn integer_edge_cases() {
+        let kernel = Kernel::parse(
+            "
+            @group(0) @binding(0) var<storage, read> ints: array<i32>;
+            @group(0) @binding(1) var<storage, read> floats: array<f32>;
+            @group(0) @binding(2) var<storage, read_write> out: array<i32>;
+
+            @compute @workgroup_size(1)
+            fn main() {
+                let x = ints[0];
+                let zero = ints[1];
+                let min = ints[2];
+                let neg = ints[3];
+                let wide = u32(ints[4]);
+                out[0] = x / zero;
+                out[1] = x % zero;
+                out[2] = min / neg;
+                out[3] = min % neg;
+                out[4] = i32(u32(x) / u32(zero));
+                out[5] = i32(u32(x) % u32(zero));
+                out[6] = neg >> 31u;
+                out[7] = i32(u32(neg) >> 31u);
+                out[8] = x << wide;
+                out[9] = i32(floats[0]);
+                out[10] = i32(floats[1]);
+                out[11] = i32(u32(floats[2]));
+                out[12] = bitcast<i32>(floats[3]);
+                out[13] = i32(u32(neg));
+                out[14] = x * min;
+            }
+            ",
+        )
+        .unwrap();
+        let ints = [7, 0, i32::MIN, -1, 33].map(|x: i32| x as u32);
+        let mut bindings = Bindings::new()
+            .with_u32s(0, &ints)
+            .with_f32s(1, &[-2.7, 3e10, -5.0, 1.0])
+            .with_u32s(2, &[0; 15]);
+        kernel.dispatch("main", [1, 1, 1], &mut bindings).unwrap();
+        let out = bindings.u32s(2).unwrap().into_iter().map(|x| x as i32);
+        assert_eq!(
+            out.collect::<Vec<_>>(),
+            [
+                7,
+                0,
+                i32::MIN,
+                0,
+                7,
+                0,
+                -1,
+                1,
+                14,
+                -2,
+                i32::MAX,
+                0,
+                1.0f32.to_bits() as i32,
+                -1,
+                i32::MIN,
+            ]
+        );
+    }
+
+    #[test]
+    fn out_of_bounds_reads_zero_and_writes_nothing() {
+        let kernel = Kernel::parse(
+            "
+            @group(0) @binding(0) var<storage, read> far: array<u32>;
+            @group(0) @binding(1) var<storage, read_write> out: array<u32>;
+            @group(0) @binding(2) var image: texture_storage_2d<rgba8unorm, read_write>;
+
+            @compute @workgroup_size(1)
+            fn main() {
+                let at = far[0];
+                out[0] = far[at];
+                out[at] = 7u;
+                let texel = textureLoad(image, vec2<i32>(i32(at), 0));
+                out[1] = u32(texel.a * 255.0);
+                textureStore(image, vec2<i32>(-1, 0), vec4<f32>(1.0));
+                out[2] = arrayLength(&out);
+            }
+            ",
+        )
+        .unwrap();
+        let texture = Texture::new(2, 1, [9, 9, 9, 9]);
+        let mut bindings = Bindings::new()
+            .with_u32s(0, &[1000])
+            .with_u32s(1, &[5; 3])
+            .with_texture(2, texture.clone());
+        kernel.dispatch("main", [1, 1, 1], &mut bindings).unwrap();
+        assert_eq!(bindings.u32s(1).unwrap(), [0, 0, 3]);
+        assert_eq!(bindings.texture(2), Some(&texture));
+    }
+
+    #[test]
+    fn runaway_loops_hit_the_step_limit() {
+        let kernel = Kernel::parse(
+            "
+            @group(0) @binding(0) var<storage, read_write> out: array<u32>;
+
+            @compute @workgroup_size(1)
+            fn main() {
+                loop {
+                    out[0] += 1u;
+                    if out[0] == 0u { break; }
+                }
+            }
+            ",
+        )
+        .unwrap()
+        .with_step_limit(1000);
+        let mut bindings = Bindings::new().with_u32s(0, &[1]);
+        let err = kernel
+            .dispatch("main", [1, 1, 1], &mut bindings)
+            .unwrap_err();
+        assert_eq!(
+            format!("{:#}", err),
+            "main at [0, 0, 0]: gave up after 1000 loop iterations"
+        );
+        assert_eq!(bindings.u32s(0).unwrap(), [1001]);
+    }
+}
@@ -0,0 +1,412 @@
+use std::collections::{BTreeMap, HashMap};
+use std::fmt::Write;
+
+use anyhow::{Context, Result};
+use serde::Deserialize;
+
+/// A private leaderboard as exported from the "API" link on its page.
+#[derive(Debug, Deserialize)]
+pub struct Leaderboard {
+    pub event: String,
+    pub members: HashMap<String, Member>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct Member {
+    pub id: u64,
+    pub name: Option<String>,
+    #[serde(default)]
+    pub completion_day_level: BTreeMap<u8, BTreeMap<u8, Star>>,
+}
+
+#[derive(Debug, Clone, Copy, Deserialize)]
+pub struct Star {
+    pub get_star_ts: i64,
+}
+
+impl Member {
+    /// The name the site shows, including for members who hide theirs.
+    pub fn display_name(&self) -> String {
+        match &self.name {
+            Some(name) => name.clone(),
+            None => format!("(anonymous user #{})", self.id),
+        }
+    }
+
+    /// When this member got `part` of `day`, as a unix timestamp.
+    pub fn star(&self, day: u8, part: u8) -> Option<i64> {
+        self.completion_day_level
+            .get(&day)?
+            .get(&part)
+            .map(|star| star.get_star_ts)
+    }
+
+    /// Every star in the order it was earned, as `(day, part, timestamp)`.
+    pub fn timeline(&self) -> Vec<(u8, u8, i64)> {
+        let mut stars = self
+            .completion_day_level
+            .iter()
+            .flat_map(|(day, parts)| {
+                parts
+                    .iter()
+                    .map(|(part, star)| (*day, *part, star.get_star_ts))
+            })
+            .collect::<Vec<_>>();
+        stars.sort_by_key(|(day, part, ts)| (*ts, *day, *part));
+        stars
+    }
+}
+
+/// Unix timestamp at which `day` of the `year` event unlocked, midnight
+/// US Eastern (05:00 UTC) on that day of December.
+pub fn unlock_time(year: i64, day: u8) -> i64 {
+    // days_from_civil for December 1st; March-based so no year shift.
+    let era = year.div_euclid(400);
+    let yoe = year - era * 400;
+    let doe = yoe * 365 + yoe / 4 - yoe / 100 + 275;
+    let days = era * 146_097 + doe - 719_468;
+    (days + day as i64 - 1) * 86_400 + 5 * 3_600
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Row {
+    pub member: String,
+    /// Seconds from unlock to each star.
+    pub part1: Option<i64>,
+    pub part2: Option<i64>,
+    /// Local score counting every star up to and including this day.
+    pub score: u64,
+    pub rank: usize,
+    /// Places gained since the previous day, negative when dropping.
+    pub change: Option<i64>,
+}
+
+impl Row {
+    /// Seconds between the first and second star.
+    pub fn delta(&self) -> Option<i64> {
+        Some(self.part2? - self.part1?)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Standings {
+    pub day: u8,
+    pub rows: Vec<Row>,
+}
+
+/// One member's stars in the order they were earned.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Timeline {
+    pub member: String,
+    /// `(day, part, seconds from that day's unlock)` for each star.
+    pub stars: Vec<(u8, u8, i64)>,
+}
+
+fn year(board: &Leaderboard) -> Result<i64> {
+    board
+        .event
+        .parse()
+        .with_context(|| format!("event {:?} is not a year", board.event))
+}
+
+fn members_by_id(board: &Leaderboard) -> Vec<&Member> {
+    let mut members = board.members.values().collect::<Vec<_>>();
+    members.sort_by_key(|member| member.id);
+    members
+}
+
+/// Every member's star timeline, in member id order.
+pub fn timelines(board: &Leaderboard) -> Result<Vec<Timeline>> {
+    let year = year(board)?;
+    Ok(members_by_id(board)
+        .into_iter()
+        .map(|member| Timeline {
+            member: member.display_name(),
+            stars: member
+                .timeline()
+                .into_iter()
+                .map(|(day, part, ts)| (day, part, ts - unlock_time(year, day)))
+                .collect(),
+        })
+        .collect())
+}
+
+/// Works out, for every day anyone has a star on, each member's star times
+/// and where local scoring puts them once that day's stars are counted.
+pub fn analyze(board: &Leaderboard) -> Result<Vec<Standings>> {
+    let year = year(board)?;
+    let members = members_by_id(board);
+    let last_day = members
+        .iter()
+        .flat_map(|member| member.completion_day_level.keys())
+        .copied()
+        .max()
+        .unwrap_or(0);
+
+    let mut scores = vec![0u64; members.len()];
+    let mut previous_ranks: Option<Vec<usize>> = None;
+    let mut standings = Vec::new();
+
+    for day in 1..=last_day {
+        // Each star is worth one point per member, minus the members who
+        // got it first.
+        for part in 1..=2 {
+            let mut finishers = members
+                .iter()
+                .enumerate()
+                .filter_map(|(idx, member)| Some((member.star(day, part)?, idx)))
+                .collect::<Vec<_>>();
+            finishers.sort();
+            for (place, (_, idx)) in finishers.into_iter().enumerate() {
+                scores[idx] += (members.len() - place) as u64;
+            }
+        }
+
+        let ranks = ranks(&scores);
+        let unlock = unlock_time(year, day);
+        let mut rows = members
+            .iter()
+            .enumerate()
+            .map(|(idx, member)| Row {
+                member: member.display_name(),
+                part1: member.star(day, 1).map(|ts| ts - unlock),
+                part2: member.star(day, 2).map(|ts| ts - unlock),
+                score: scores[idx],
+                rank: ranks[idx],
+                change: previous_ranks
+                    .as_ref()
+                    .map(|previous| previous[idx] as i64 - ranks[idx] as i64),
+            })
+            .collect::<Vec<_>>();
+        rows.sort_by(|a, b| a.rank.cmp(&b.rank).then_with(|| a.member.cmp(&b.member)));
+
+        standings.push(Standings { day, rows });
+        previous_ranks = Some(ranks);
+    }
+
+    Ok(standings)
+}
+
+/// 1-based competition ranks, members on the same score sharing a place.
+fn ranks(scores: &[u64]) -> Vec<usize> {
+    scores
+        .iter()
+        .map(|score| scores.iter().filter(|other| *other > score).count() + 1)
+        .collect()
+}
+
+fn duration(seconds: Option<i64>) -> String {
+    match seconds {
+        Some(seconds) => format!(
+            "{:02}:{:02}:{:02}",
+            seconds / 3_600,
+            seconds % 3_600 / 60,
+            seconds % 60
+        ),
+        None => "-".to_owned(),
+    }
+}
+
+/// Renders the standings as one table per day, followed by each member's
+/// star timeline.
+pub fn render(standings: &[Standings], timelines: &[Timeline]) -> String {
+    let width = standings
+        .iter()
+        .flat_map(|day| &day.rows)
+        .map(|row| row.member.as_str())
+        .chain(timelines.iter().map(|timeline| timeline.member.as_str()))
+        .map(|member| member.chars().count())
+        .max()
+        .unwrap_or(0)
+        .max("member".len());
+
+    let mut out = String::new();
+    for day in standings {
+        writeln!(out, "Day {}", day.day).unwrap();
+        writeln!(
+            out,
+            "{:>4}  {:<width$}  {:>10}  {:>10}  {:>10}  {:>5}  {:>4}",
+            "rank",
+            "member",
+            "part 1",
+            "part 2",
+            "delta",
+            "score",
+            "+/-",
+            width = width
+        )
+        .unwrap();
+        for row in &day.rows {
+            let change = match row.change {
+                Some(0) | None => "".to_owned(),
+                Some(change) => format!("{:+}", change),
+            };
+            writeln!(
+                out,
+                "{:>4}  {:<width$}  {:>10}  {:>10}  {:>10}  {:>5}  {:>4}",
+                row.rank,
+                row.member,
+                duration(row.part1),
+                duration(row.part2),
+                duration(row.delta()),
+                row.score,
+                change,
+                width = width
+            )
+            .unwrap();
+        }
+        out.push('\n');
+    }
+
+    if !timelines.is_empty() {
+        writeln!(out, "Timelines").unwrap();
+    }
+    for timeline in timelines {
+        let stars = timeline
+            .stars
+            .iter()
+            .map(|(day, part, seconds)| format!("{}.{} {}", day, part, duration(Some(*seconds))))
+            .collect::<Vec<_>>();
+        let stars = if stars.is_empty() {
+            "-".to_owned()
+        } else {
+            stars.join("  ")
+        };
+        writeln!(out, "{:<width$}  {}", timeline.member, stars, width = width).unwrap();
+    }
+    out
+}
+
+pub fn parse(json: &str) -> Result<Leaderboard> {
+    Ok(serde_json::from_str(json)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DEC_1: i64 = 1_669_870_800;
+    const DEC_2: i64 = DEC_1 + 86_400;
+
+    fn board() -> Leaderboard {
+        let json = format!(
+            r#"{{
+                "event": "2022",
+                "owner_id": 1,
+                "members": {{
+                    "1": {{"id": 1, "name": "ada", "stars": 3, "local_score": 0,
+                        "completion_day_level": {{
+                            "1": {{"1": {{"get_star_ts": {a1}, "star_index": 0}},
+                                   "2": {{"get_star_ts": {a2}, "star_index": 1}}}},
+                            "2": {{"1": {{"get_star_ts": {a3}, "star_index": 5}}}}
+                        }}}},
+                    "2": {{"id": 2, "name": null, "stars": 4, "local_score": 0,
+                        "completion_day_level": {{
+                            "1": {{"1": {{"get_star_ts": {b1}, "star_index": 2}},
+                                   "2": {{"get_star_ts": {b2}, "star_index": 3}}}},
+                            "2": {{"1": {{"get_star_ts": {b3}, "star_index": 4}},
+                                   "2": {{"get_star_ts": {b4}, "star_index": 6}}}}
+                        }}}},
+                    "3": {{"id": 3, "name": "lurker", "stars": 0, "local_score": 0,
+                        "completion_day_level": {{}}}}
+                }}
+            }}"#,
+            a1 = DEC_1 + 300,
+            a2 = DEC_1 + 600,
+            a3 = DEC_2 + 400,
+            b1 = DEC_1 + 400,
+            b2 = DEC_1 + 4_000,
+            b3 = DEC_2 + 100,
+            b4 = DEC_2 + 200,
+        );
+        parse(&json).unwrap()
+    }
+
+    #[test]
+    fn unlocks_at_midnight_eastern() {
+        assert_eq!(unlock_time(2022, 1), DEC_1);
+        assert_eq!(unlock_time(2022, 2), DEC_2);
+        assert_eq!(unlock_time(2015, 25), 1_451_019_600);
+    }
+
+    #[test]
+    fn timeline_is_in_order() {
+        let board = board();
+        let timeline = board.members["2"].timeline();
+        assert_eq!(
+            timeline,
+            vec![
+                (1, 1, DEC_1 + 400),
+                (1, 2, DEC_1 + 4_000),
+                (2, 1, DEC_2 + 100),
+                (2, 2, DEC_2 + 200)
+            ]
+        );
+        assert_eq!(board.members["2"].display_name(), "(anonymous user #2)");
+    }
+
+    #[test]
+    fn scores_and_rank_changes() {
+        let standings = analyze(&board()).unwrap();
+        assert_eq!(standings.len(), 2);
+
+        let day1 = &standings[0].rows;
+        assert_eq!(day1[0].member, "ada");
+        assert_eq!((day1[0].score, day1[0].rank), (6, 1));
+        assert_eq!((day1[0].part1, day1[0].delta()), (Some(300), Some(300)));
+        assert_eq!((day1[1].score, day1[1].rank, day1[1].change), (4, 2, None));
+        assert_eq!((day1[2].member.as_str(), day1[2].score), ("lurker", 0));
+
+        let day2 = &standings[1].rows;
+        assert_eq!(day2[0].member, "(anonymous user #2)");
+        assert_eq!((day2[0].score, day2[0].change), (10, Some(1)));
+        assert_eq!((day2[1].score, day2[1].change), (8, Some(-1)));
+        assert_eq!((day2[1].part2, day2[1].delta()), (None, None));
+
+        let table = render(&standings, &[]);
+        let ada = ["1", "ada", "00:05:00", "00:10:00", "00:05:00", "6"];
+        assert!(table.lines().any(|line| line.split_whitespace().eq(ada)));
+    }
+
+    #[test]
+    fn renders_timelines() {
+        let board = board();
+        let report = render(&analyze(&board).unwrap(), &timelines(&board).unwrap());
+        let timelines = report
+            .lines()
+            .skip_while(|line| *line != "Timelines")
+            .collect::<Vec<_>>();
+        assert_eq!(
+            timelines,
+            [
+                "Timelines",
+                "ada                  1.1 00:05:00  1.2 00:10:00  2.1 00:06:40",
+                "(anonymous user #2)  1.1 00:06:40  1.2 01:06:40  2.1 00:01:40  2.2 00:03:20",
+                "lurker               -",
+            ]
+        );
+    }
+
+    #[test]
+    fn pads_names_by_char() {
+        let row = Row {
+            member: "Zoë Ångström".to_owned(),
+            part1: None,
+            part2: None,
+            score: 0,
+            rank: 1,
+            change: None,
+        };
+        let standings = [Standings {
+            day: 1,
+            rows: vec![row],
+        }];
+        let table = render(&standings, &[]);
+        let widths = table
+            .lines()
+            .skip(1)
+            .map(|line| line.chars().count())
+            .collect::<Vec<_>>();
+        assert_eq!(widths, [67, 67, 0]);
+    }
+}
@@ -1 +1,21 @@
 pub mod args;
+pub mod days;
+pub mod describe;
+pub mod error;
+pub mod ffi;
+pub mod geom;
+pub mod grid;
+pub mod harness;
+pub mod identify;
+pub mod inputs;
+#[cfg(feature = "kernel")]
+pub mod kernel;
+pub mod leaderboard;
+pub mod lint;
+pub mod parse;
+pub mod raster;
+pub mod runner;
+pub mod search;
+pub mod serve;
+pub mod shaders;
+pub mod visualize;
@@ -0,0 +1,171 @@
+use std::fmt;
+
+use anyhow::{bail, Result};
+
+use crate::days;
+
+pub mod y2022;
+
+/// A structural problem found in an input file. Lines and columns are 1-based.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Violation {
+    pub line: usize,
+    pub column: Option<usize>,
+    pub message: String,
+}
+
+impl Violation {
+    fn new(line: usize, message: impl Into<String>) -> Self {
+        Violation {
+            line,
+            column: None,
+            message: message.into(),
+        }
+    }
+
+    fn at(line: usize, column: usize, message: impl Into<String>) -> Self {
+        Violation {
+            line,
+            column: Some(column),
+            message: message.into(),
+        }
+    }
+}
+
+impl fmt::Display for Violation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.column {
+            Some(column) => write!(f, "{}:{}: {}", self.line, column, self.message),
+            None => write!(f, "{}: {}", self.line, self.message),
+        }
+    }
+}
+
+/// Checks `content` against the structural invariants of `day`'s puzzle input.
+/// Every violation is reported, not just the first one.
+pub fn lint(year: u16, day: u8, content: &str) -> Result<Vec<Violation>> {
+    let rules = match days::info(year, day).and_then(|info| info.lint) {
+        Some(rules) => rules,
+        None => bail!("no lint rules for {} day {}", year, day),
+    };
+    let mut violations = rules(content);
+    if content.trim().is_empty() {
+        violations.insert(0, Violation::new(1, "input is empty"));
+    }
+    Ok(violations)
+}
+
+fn numbered(content: &str) -> impl Iterator<Item = (usize, &str)> {
+    content
+        .lines()
+        .enumerate()
+        .map(|(idx, line)| (idx + 1, line))
+}
+
+/// The 1-based column of the first char in `line` that fails `valid`.
+fn first_invalid(line: &str, valid: impl Fn(char) -> bool) -> Option<(usize, char)> {
+    line.chars()
+        .enumerate()
+        .find(|&(_, c)| !valid(c))
+        .map(|(idx, c)| (idx + 1, c))
+}
+
+/// The 1-based column just past `prefix` and the space after it.
+fn column_after(prefix: &str) -> usize {
+    prefix.chars().count() + 2
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn examples_are_clean() {
+        let examples = [
+            (1, include_str!("../../inputs/2022/day1_example.txt")),
+            (2, include_str!("../../inputs/2022/day2_example.txt")),
+            (3, include_str!("../../inputs/2022/day3_example.txt")),
+            (4, include_str!("../../inputs/2022/day4_example.txt")),
+            (5, include_str!("../../inputs/2022/day5_example.txt")),
+            (6, include_str!("../../inputs/2022/day6_example.txt")),
+            (7, include_str!("../../inputs/2022/day7_example.txt")),
+            (8, include_str!("../../inputs/2022/day8_example.txt")),
+            (9, include_str!("../../inputs/2022/day9_example.txt")),
+        ];
+        for (day, content) in examples {
+            assert_eq!(lint(2022, day, content).unwrap(), vec![], "day {}", day);
+        }
+    }
+
+    #[test]
+    fn calories_lint_like_they_parse() {
+        let content = "1000\n  \n\n\t\n-5\n2000\n\n\n";
+        assert_eq!(lint(2022, 1, content).unwrap(), vec![]);
+        assert!(crate::days::y2022::day1::parse(content).is_ok());
+
+        let content = "1000\n 2000\n4294967295\n";
+        let lines = lint(2022, 1, content)
+            .unwrap()
+            .iter()
+            .map(|v| v.line)
+            .collect::<Vec<_>>();
+        assert_eq!(lines, vec![2, 3]);
+        assert!(crate::days::y2022::day1::parse(" 2000\n").is_err());
+        assert!(crate::days::y2022::day1::parse("4294967295\n").is_err());
+    }
+
+    #[test]
+    fn rules_come_from_the_registry() {
+        for year in crate::days::YEARS {
+            for info in year.days {
+                let linted = lint(year.year, info.day, "1\n");
+                assert_eq!(linted.is_ok(), info.lint.is_some(), "day {}", info.day);
+            }
+        }
+        assert!(lint(2022, 26, "1\n").is_err());
+        assert!(lint(2015, 1, "1\n").is_err());
+    }
+
+    #[test]
+    fn ragged_grid() {
+        let violations = lint(2022, 8, "30373\n2551\n65332\n").unwrap();
+        assert_eq!(
+            violations,
+            vec![Violation::new(2, "row has 4 trees, expected 5")]
+        );
+    }
+
+    #[test]
+    fn move_from_empty_stack() {
+        let content = "[A]    \n 1   2 \n\nmove 1 from 1 to 2\nmove 1 from 1 to 2\n";
+        let violations = lint(2022, 5, content).unwrap();
+        assert_eq!(violations[0].line, 5);
+        assert!(violations[0].message.contains("only holds 0"));
+    }
+
+    #[test]
+    fn reports_every_violation() {
+        let violations = lint(2022, 9, "R 4\nX 2\nU 0\nL\n").unwrap();
+        let lines = violations.iter().map(|v| v.line).collect::<Vec<_>>();
+        assert_eq!(lines, vec![2, 3, 4]);
+    }
+
+    #[test]
+    fn non_ascii_columns_count_chars() {
+        assert_eq!(
+            lint(2022, 3, "abé\n").unwrap()[0],
+            Violation::at(1, 3, "item must be a letter, found \"é\"")
+        );
+        assert_eq!(
+            lint(2022, 8, "30373\n2é551\n").unwrap(),
+            vec![Violation::at(2, 2, "tree height must be a digit")]
+        );
+        assert_eq!(
+            lint(2022, 9, "É 0\n").unwrap()[1],
+            Violation::at(1, 3, "motion count must be positive")
+        );
+        for day in 1..=9 {
+            lint(2022, day, "héllo\n¤ ☃\n").unwrap();
+        }
+    }
+}
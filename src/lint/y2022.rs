@@ -0,0 +1,463 @@
+//! Lint rules for the 2022 puzzles.
+
+use std::collections::HashSet;
+
+use super::{column_after, first_invalid, numbered, Violation};
+
+/// Blank lines, whitespace included, separate elves, and any number of them
+/// is one break, as `parse::blocks` reads them.
+pub fn day1(content: &str) -> Vec<Violation> {
+    let mut violations = Vec::new();
+
+    for (n, line) in numbered(content) {
+        if line.trim().is_empty() {
+            continue;
+        }
+        if line.parse::<i32>().is_err() {
+            violations.push(Violation::new(
+                n,
+                format!("expected a calorie count, found {:?}", line),
+            ));
+        }
+    }
+
+    violations
+}
+
+pub fn day2(content: &str) -> Vec<Violation> {
+    let mut violations = Vec::new();
+
+    for (n, line) in numbered(content) {
+        let parts = line.split(' ').collect::<Vec<_>>();
+        if parts.len() != 2 {
+            violations.push(Violation::new(
+                n,
+                format!(
+                    "expected two letters separated by a space, found {:?}",
+                    line
+                ),
+            ));
+            continue;
+        }
+        if !matches!(parts[0], "A" | "B" | "C") {
+            violations.push(Violation::at(
+                n,
+                1,
+                format!("opponent move must be A, B or C, found {:?}", parts[0]),
+            ));
+        }
+        if !matches!(parts[1], "X" | "Y" | "Z") {
+            violations.push(Violation::at(
+                n,
+                column_after(parts[0]),
+                format!("response must be X, Y or Z, found {:?}", parts[1]),
+            ));
+        }
+    }
+
+    violations
+}
+
+pub fn day3(content: &str) -> Vec<Violation> {
+    let mut violations = Vec::new();
+    let lines = content.lines().collect::<Vec<_>>();
+
+    for (n, line) in numbered(content) {
+        if let Some((column, c)) = first_invalid(line, |c| c.is_ascii_alphabetic()) {
+            violations.push(Violation::at(
+                n,
+                column,
+                format!("item must be a letter, found {:?}", c.to_string()),
+            ));
+            continue;
+        }
+        if line.is_empty() || line.len() % 2 != 0 {
+            violations.push(Violation::new(
+                n,
+                format!(
+                    "rucksack must split into two equal compartments, length is {}",
+                    line.len()
+                ),
+            ));
+            continue;
+        }
+        let (a, b) = line.split_at(line.len() / 2);
+        if !a.chars().any(|c| b.contains(c)) {
+            violations.push(Violation::new(n, "compartments share no item"));
+        }
+    }
+
+    if lines.len() % 3 != 0 {
+        violations.push(Violation::new(
+            lines.len(),
+            format!("rucksack count {} is not a multiple of 3", lines.len()),
+        ));
+    }
+    for (group, chunk) in lines.chunks(3).enumerate() {
+        if chunk.len() == 3
+            && !chunk[0]
+                .chars()
+                .any(|c| chunk[1].contains(c) && chunk[2].contains(c))
+        {
+            violations.push(Violation::new(
+                group * 3 + 1,
+                "group of three shares no badge",
+            ));
+        }
+    }
+
+    violations
+}
+
+fn parse_range(part: &str) -> Option<(u32, u32)> {
+    let (start, end) = part.split_once('-')?;
+    Some((start.parse().ok()?, end.parse().ok()?))
+}
+
+pub fn day4(content: &str) -> Vec<Violation> {
+    let mut violations = Vec::new();
+
+    for (n, line) in numbered(content) {
+        let ranges = line
+            .split_once(',')
+            .and_then(|(a, b)| Some((parse_range(a)?, parse_range(b)?)));
+        match ranges {
+            None => violations.push(Violation::new(
+                n,
+                format!("expected a-b,c-d, found {:?}", line),
+            )),
+            Some(((a, b), (c, d))) => {
+                if a > b {
+                    violations.push(Violation::at(
+                        n,
+                        1,
+                        format!("range {}-{} is reversed", a, b),
+                    ));
+                }
+                if c > d {
+                    let (first, _) = line.split_once(',').unwrap();
+                    violations.push(Violation::at(
+                        n,
+                        column_after(first),
+                        format!("range {}-{} is reversed", c, d),
+                    ));
+                }
+            }
+        }
+    }
+
+    violations
+}
+
+pub fn day5(content: &str) -> Vec<Violation> {
+    let mut violations = Vec::new();
+    let lines = content.lines().collect::<Vec<_>>();
+
+    let header = match lines
+        .iter()
+        .position(|line| line.trim_start().starts_with('1'))
+    {
+        Some(idx) => idx,
+        None => return vec![Violation::new(1, "no stack number header found")],
+    };
+
+    let labels = lines[header].split_whitespace().collect::<Vec<_>>();
+    let count = labels.len();
+    for (idx, label) in labels.iter().enumerate() {
+        if label.parse::<usize>().ok() != Some(idx + 1) {
+            violations.push(Violation::new(
+                header + 1,
+                format!(
+                    "stack labels must count up from 1, found {:?} at position {}",
+                    label,
+                    idx + 1
+                ),
+            ));
+            break;
+        }
+    }
+    let header_chars = lines[header].chars().collect::<Vec<_>>();
+    for (idx, label) in labels.iter().enumerate() {
+        let expected = idx * 4 + 1;
+        let width = label.chars().count();
+        let found = header_chars.get(expected..expected + width);
+        if found
+            .map(|found| found.iter().collect::<String>())
+            .as_deref()
+            != Some(label)
+        {
+            violations.push(Violation::new(
+                header + 1,
+                format!(
+                    "stack label {:?} is not aligned with column {}",
+                    label,
+                    expected + 1
+                ),
+            ));
+            break;
+        }
+    }
+
+    let mut heights = vec![0usize; count];
+    for (idx, row) in lines[..header].iter().enumerate().rev() {
+        let n = idx + 1;
+        let chars = row.chars().collect::<Vec<_>>();
+        for (stack, cell) in chars.chunks(4).enumerate() {
+            let crate_cell = match cell {
+                ['[', c, ']', ..] if c.is_ascii_uppercase() => true,
+                _ if cell.iter().all(|c| *c == ' ') => false,
+                _ => {
+                    violations.push(Violation::at(
+                        n,
+                        stack * 4 + 1,
+                        format!(
+                            "malformed crate {:?}",
+                            cell.iter().take(3).collect::<String>()
+                        ),
+                    ));
+                    continue;
+                }
+            };
+            if !crate_cell {
+                continue;
+            }
+            if stack >= count {
+                violations.push(Violation::at(
+                    n,
+                    stack * 4 + 1,
+                    format!(
+                        "crate in column {} but the header only names {} stacks",
+                        stack + 1,
+                        count
+                    ),
+                ));
+            } else if heights[stack] != lines[..header].len() - 1 - idx {
+                violations.push(Violation::at(
+                    n,
+                    stack * 4 + 1,
+                    "crate is floating above an empty slot",
+                ));
+            } else {
+                heights[stack] += 1;
+            }
+        }
+    }
+
+    match lines.get(header + 1) {
+        Some(&"") => {}
+        _ => violations.push(Violation::new(
+            header + 2,
+            "expected a blank line after the stack header",
+        )),
+    }
+
+    let re = regex::Regex::new(r"^move (\d+) from (\d+) to (\d+)$").unwrap();
+    for (idx, line) in lines.iter().enumerate().skip(header + 2) {
+        let n = idx + 1;
+        let caps = match re.captures(line) {
+            Some(caps) => caps,
+            None => {
+                violations.push(Violation::new(
+                    n,
+                    format!("expected \"move N from A to B\", found {:?}", line),
+                ));
+                continue;
+            }
+        };
+        let num = |i: usize| caps[i].parse::<usize>().unwrap_or(usize::MAX);
+        let (moved, from, to) = (num(1), num(2), num(3));
+
+        let mut valid = true;
+        for stack in [from, to] {
+            if stack == 0 || stack > count {
+                violations.push(Violation::new(n, format!("stack {} does not exist", stack)));
+                valid = false;
+            }
+        }
+        if !valid {
+            continue;
+        }
+        if moved == 0 {
+            violations.push(Violation::new(n, "move count must be positive"));
+        }
+        if heights[from - 1] < moved {
+            violations.push(Violation::new(
+                n,
+                format!(
+                    "moves {} crates from stack {} which only holds {}",
+                    moved,
+                    from,
+                    heights[from - 1]
+                ),
+            ));
+            heights[to - 1] += heights[from - 1];
+            heights[from - 1] = 0;
+        } else {
+            heights[from - 1] -= moved;
+            heights[to - 1] += moved;
+        }
+    }
+
+    for (stack, height) in heights.iter().enumerate() {
+        if *height == 0 {
+            violations.push(Violation::new(
+                lines.len(),
+                format!("stack {} is empty after the last move", stack + 1),
+            ));
+        }
+    }
+
+    violations
+}
+
+pub fn day6(content: &str) -> Vec<Violation> {
+    let mut violations = Vec::new();
+    let lines = content.lines().collect::<Vec<_>>();
+
+    if lines.len() > 1 {
+        violations.push(Violation::new(2, "datastream must be a single line"));
+    }
+    let stream = lines.first().copied().unwrap_or_default();
+    if let Some((column, _)) = first_invalid(stream, |c| c.is_ascii_lowercase()) {
+        violations.push(Violation::at(
+            1,
+            column,
+            "datastream must only contain lowercase letters",
+        ));
+    }
+
+    let chars = stream.chars().collect::<Vec<_>>();
+    for size in [4, 14] {
+        if !chars
+            .windows(size)
+            .any(|window| window.iter().collect::<HashSet<_>>().len() == size)
+        {
+            violations.push(Violation::new(
+                1,
+                format!("no run of {} distinct characters", size),
+            ));
+        }
+    }
+
+    violations
+}
+
+pub fn day7(content: &str) -> Vec<Violation> {
+    let mut violations = Vec::new();
+    let mut dirs = HashSet::from([String::new()]);
+    let mut files = HashSet::new();
+    let mut current = Vec::<&str>::new();
+    let mut listing = false;
+
+    for (n, line) in numbered(content) {
+        if let Some(target) = line.strip_prefix("$ cd ") {
+            listing = false;
+            match target {
+                "/" => current.clear(),
+                ".." => {
+                    if current.pop().is_none() {
+                        violations.push(Violation::new(n, "cd .. from the root directory"));
+                    }
+                }
+                name => {
+                    current.push(name);
+                    if !dirs.contains(&path(&current)) {
+                        violations.push(Violation::at(
+                            n,
+                            6,
+                            format!("cd into {:?} which was never listed", path(&current)),
+                        ));
+                        dirs.insert(path(&current));
+                    }
+                }
+            }
+        } else if line == "$ ls" {
+            listing = true;
+        } else if line.starts_with('$') {
+            violations.push(Violation::new(n, format!("unknown command {:?}", line)));
+        } else if !listing {
+            violations.push(Violation::new(n, "directory entry outside of ls output"));
+        } else if let Some(name) = line.strip_prefix("dir ") {
+            dirs.insert(path(&current) + "/" + name);
+        } else {
+            match line.split_once(' ') {
+                Some((size, name)) if size.parse::<usize>().is_ok() && !name.is_empty() => {
+                    if !files.insert(path(&current) + "/" + name) {
+                        violations.push(Violation::new(n, format!("file {:?} listed twice", name)));
+                    }
+                }
+                _ => violations.push(Violation::new(
+                    n,
+                    format!("expected \"dir NAME\" or \"SIZE NAME\", found {:?}", line),
+                )),
+            }
+        }
+    }
+
+    violations
+}
+
+fn path(parts: &[&str]) -> String {
+    parts
+        .iter()
+        .fold(String::new(), |acc, part| acc + "/" + part)
+}
+
+pub fn day8(content: &str) -> Vec<Violation> {
+    let mut violations = Vec::new();
+    let trees = |line: &str| line.chars().count();
+    let width = content.lines().next().map(trees).unwrap_or_default();
+
+    for (n, line) in numbered(content) {
+        if trees(line) != width {
+            violations.push(Violation::new(
+                n,
+                format!("row has {} trees, expected {}", trees(line), width),
+            ));
+        }
+        if let Some((column, _)) = first_invalid(line, |c| c.is_ascii_digit()) {
+            violations.push(Violation::at(n, column, "tree height must be a digit"));
+        }
+    }
+
+    violations
+}
+
+pub fn day9(content: &str) -> Vec<Violation> {
+    let mut violations = Vec::new();
+
+    for (n, line) in numbered(content) {
+        let (dir, count) = match line.split_once(' ') {
+            Some(parts) => parts,
+            None => {
+                violations.push(Violation::new(
+                    n,
+                    format!("expected a direction and a count, found {:?}", line),
+                ));
+                continue;
+            }
+        };
+        if !matches!(dir, "U" | "R" | "D" | "L") {
+            violations.push(Violation::at(
+                n,
+                1,
+                format!("direction must be U, R, D or L, found {:?}", dir),
+            ));
+        }
+        match count.parse::<u32>() {
+            Ok(0) => violations.push(Violation::at(
+                n,
+                column_after(dir),
+                "motion count must be positive",
+            )),
+            Ok(_) => {}
+            Err(_) => violations.push(Violation::at(
+                n,
+                column_after(dir),
+                format!("motion count must be a number, found {:?}", count),
+            )),
+        }
+    }
+
+    violations
+}
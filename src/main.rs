@@ -1,5 +1,142 @@
-pub mod args;
+use std::time::Duration;
+
+use anyhow::anyhow;
+use structopt::StructOpt;
+
+use advent::args::Command;
+use advent::error::{read_input, Error};
+use advent::{days, describe, identify, inputs, leaderboard, lint, serve, visualize};
 
 fn main() {
-    println!("Hello, world!");
+    if let Err(err) = run(Command::from_args()) {
+        eprintln!("error: {}", err);
+        std::process::exit(err.exit_code());
+    }
+}
+
+fn run(command: Command) -> Result<(), Error> {
+    match command {
+        Command::Lint { day, year, input } => {
+            let content = read_input(&input)?;
+            let violations = lint::lint(year, day, &content).map_err(Error::Unavailable)?;
+            for violation in &violations {
+                println!("{}:{}", input.display(), violation);
+            }
+            if !violations.is_empty() {
+                return Err(Error::Parse(anyhow!(
+                    "{} problem(s) found",
+                    violations.len()
+                )));
+            }
+        }
+        Command::List => {
+            println!("{:>4}  {:>3}  {:<24}  backends", "year", "day", "title");
+            for year in days::YEARS {
+                for info in year.days {
+                    let backends = info
+                        .backends
+                        .iter()
+                        .map(|backend| backend.to_string())
+                        .collect::<Vec<_>>();
+                    println!(
+                        "{:>4}  {:>3}  {:<24}  {}",
+                        year.year,
+                        info.day,
+                        info.title,
+                        backends.join(", ")
+                    );
+                }
+            }
+        }
+        Command::Serve { port, timeout } => {
+            let network = |err| Error::Network(anyhow::Error::from(err));
+            let listener = std::net::TcpListener::bind(("127.0.0.1", port)).map_err(network)?;
+            println!(
+                "listening on http://{}",
+                listener.local_addr().map_err(network)?
+            );
+            serve::serve(listener, Some(timeout)).map_err(Error::Network)?;
+        }
+        Command::Identify { file } => {
+            let content = read_input(&file)?;
+            match identify::identify(&content) {
+                Some(found) => {
+                    println!(
+                        "{} day {} (confidence {:.0}%)",
+                        found.year,
+                        found.day,
+                        found.confidence * 100.0
+                    );
+                    for candidate in &found.candidates {
+                        println!(
+                            "  {} day {}: {:.2}",
+                            candidate.year, candidate.day, candidate.score
+                        );
+                    }
+                }
+                None => {
+                    return Err(Error::Parse(anyhow!(
+                        "{} doesn't look like any known day",
+                        file.display()
+                    )))
+                }
+            }
+        }
+        Command::Describe {
+            day,
+            year,
+            base_url,
+            cache_dir,
+            inputs,
+        } => {
+            let page = describe::cached(&cache_dir, &base_url, year, day).map_err(|err| {
+                if err.chain().any(|cause| cause.is::<ureq::Error>()) {
+                    Error::Network(err)
+                } else {
+                    Error::Output(err)
+                }
+            })?;
+            let articles = describe::articles(&page);
+            for article in &articles {
+                println!("{}", describe::to_markdown(article));
+            }
+            let inputs = inputs.join(year.to_string());
+            for path in describe::save_examples(&inputs, day, &articles).map_err(Error::Output)? {
+                eprintln!("wrote {}", path.display());
+            }
+        }
+        Command::Play {
+            day,
+            year,
+            input,
+            fps,
+            save,
+        } => {
+            let visualizer = days::info(year, day)
+                .and_then(|info| info.visualize)
+                .ok_or_else(|| {
+                    Error::Unavailable(anyhow!("{} day {} has no visualization", year, day))
+                })?;
+            let input = input.unwrap_or_else(|| inputs::path(year, day));
+            let frames = visualizer(&read_input(&input)?).map_err(Error::from_solver)?;
+            match save {
+                Some(path) => {
+                    let delay = Duration::from_secs_f64(1.0 / fps);
+                    visualize::animate(&frames, delay)
+                        .save_gif(&path)
+                        .map_err(Error::Output)?;
+                    eprintln!("wrote {} frames to {}", frames.len(), path.display());
+                }
+                None => visualize::play(&frames, fps).map_err(Error::Output)?,
+            }
+        }
+        Command::Leaderboard { file } => {
+            let board = leaderboard::parse(&read_input(&file)?).map_err(Error::Parse)?;
+            let standings = leaderboard::analyze(&board).map_err(Error::Parse)?;
+            let timelines = leaderboard::timelines(&board).map_err(Error::Parse)?;
+            print!("{}", leaderboard::render(&standings, &timelines));
+        }
+    }
+
+    Ok(())
 }
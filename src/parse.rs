@@ -0,0 +1,211 @@
+//! Helpers for pulling puzzle inputs apart.
+//!
+//! Every failure is reported through [`invalid`], with the offending line or
+//! group in the message, so it's classified as bad input.
+
+use std::fmt::Display;
+use std::str::FromStr;
+
+use anyhow::Result;
+use regex::{Captures, Regex};
+
+use crate::error::invalid;
+
+/// Every run of digits in `line`, with a leading `-` making it negative.
+pub fn ints<T>(line: &str) -> Result<Vec<T>>
+where
+    T: FromStr,
+    T::Err: Display,
+{
+    numbers(line, true)
+}
+
+/// Every run of digits in `line`, ignoring signs.
+pub fn uints<T>(line: &str) -> Result<Vec<T>>
+where
+    T: FromStr,
+    T::Err: Display,
+{
+    numbers(line, false)
+}
+
+fn numbers<T>(line: &str, signed: bool) -> Result<Vec<T>>
+where
+    T: FromStr,
+    T::Err: Display,
+{
+    let bytes = line.as_bytes();
+    let mut numbers = Vec::new();
+    let mut i = 0;
+    while i < bytes.len() {
+        if !bytes[i].is_ascii_digit() {
+            i += 1;
+            continue;
+        }
+        let mut start = i;
+        if signed && start > 0 && bytes[start - 1] == b'-' {
+            start -= 1;
+        }
+        while i < bytes.len() && bytes[i].is_ascii_digit() {
+            i += 1;
+        }
+        let text = &line[start..i];
+        let number = text
+            .parse()
+            .map_err(|err| invalid(format!("{:?} in {:?}: {}", text, line, err)))?;
+        numbers.push(number);
+    }
+    Ok(numbers)
+}
+
+/// A tuple that can be parsed from a regex's capture groups, one field per
+/// group.
+pub trait FromCaptures: Sized {
+    fn from_captures(captures: &Captures) -> Result<Self>;
+}
+
+fn group<T>(captures: &Captures, index: usize) -> Result<T>
+where
+    T: FromStr,
+    T::Err: Display,
+{
+    let text = captures
+        .get(index)
+        .ok_or_else(|| invalid(format!("group {} is missing", index)))?
+        .as_str();
+    text.parse()
+        .map_err(|err| invalid(format!("group {} ({:?}): {}", index, text, err)))
+}
+
+macro_rules! tuple_captures {
+    ($(($($ty:ident $index:literal),+))*) => {$(
+        impl<$($ty),+> FromCaptures for ($($ty,)+)
+        where
+            $($ty: FromStr, $ty::Err: Display,)+
+        {
+            fn from_captures(captures: &Captures) -> Result<Self> {
+                Ok(($(group::<$ty>(captures, $index)?,)+))
+            }
+        }
+    )*};
+}
+
+tuple_captures! {
+    (A 1)
+    (A 1, B 2)
+    (A 1, B 2, C 3)
+    (A 1, B 2, C 3, D 4)
+    (A 1, B 2, C 3, D 4, E 5)
+    (A 1, B 2, C 3, D 4, E 5, F 6)
+}
+
+/// Matches `re` against `line` and parses its groups into a tuple.
+pub fn captures<T: FromCaptures>(re: &Regex, line: &str) -> Result<T> {
+    captures_opt(re, line)?.ok_or_else(|| invalid(format!("{:?} doesn't match {}", line, re)))
+}
+
+/// Like [`captures`], but `None` if `re` doesn't match at all.
+pub fn captures_opt<T: FromCaptures>(re: &Regex, line: &str) -> Result<Option<T>> {
+    match re.captures(line) {
+        Some(caps) => T::from_captures(&caps)
+            .map(Some)
+            .map_err(|err| invalid(format!("{:?}: {}", line, err))),
+        None => Ok(None),
+    }
+}
+
+/// Splits `text` into the blocks of lines separated by blank lines. Each
+/// block keeps its lines' leading whitespace, and runs of blank lines never
+/// yield empty blocks.
+pub fn blocks(text: &str) -> impl Iterator<Item = &str> + '_ {
+    let mut rest = text;
+    std::iter::from_fn(move || {
+        // Skip blank lines before the block.
+        loop {
+            let end = rest.find('\n').map_or(rest.len(), |i| i + 1);
+            if end == 0 || !rest[..end].trim().is_empty() {
+                break;
+            }
+            rest = &rest[end..];
+        }
+        if rest.is_empty() {
+            return None;
+        }
+        let mut end = 0;
+        while end < rest.len() {
+            let line_end = rest[end..].find('\n').map_or(rest.len(), |i| end + i + 1);
+            if rest[end..line_end].trim().is_empty() {
+                break;
+            }
+            end = line_end;
+        }
+        let block = rest[..end].trim_end_matches(['\n', '\r']);
+        rest = &rest[end..];
+        Some(block)
+    })
+}
+
+/// Maps `f` over the lines of `text`, prefixing any error with the line's
+/// number.
+pub fn map_lines<'a, T>(text: &'a str, mut f: impl FnMut(&'a str) -> Result<T>) -> Result<Vec<T>> {
+    text.lines()
+        .enumerate()
+        .map(|(n, line)| f(line).map_err(|err| invalid(format!("line {}: {:#}", n + 1, err))))
+        .collect()
+}
+
+/// Parses each line of `text` with its `FromStr` impl.
+pub fn lines<T>(text: &str) -> Result<Vec<T>>
+where
+    T: FromStr,
+    T::Err: Display,
+{
+    map_lines(text, |line| {
+        line.parse()
+            .map_err(|err| invalid(format!("{:?}: {}", line, err)))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::error::ParseError;
+
+    #[test]
+    fn numbers() {
+        assert_eq!(ints::<i32>("x=-3, y=14..-2").unwrap(), vec![-3, 14, -2]);
+        assert_eq!(uints::<u32>("2-4,6-8").unwrap(), vec![2, 4, 6, 8]);
+        assert!(uints::<u8>("300").is_err());
+        assert!(ints::<i32>("none here").unwrap().is_empty());
+    }
+
+    #[test]
+    fn typed_captures() {
+        let re = Regex::new(r"move (\d+) from (\d+) to (\w+)").unwrap();
+        let (count, from, to): (usize, u8, String) = captures(&re, "move 3 from 1 to 2").unwrap();
+        assert_eq!((count, from, to.as_str()), (3, 1, "2"));
+
+        let err = captures::<(u8, u8, u8)>(&re, "move 3 from 1 to x").unwrap_err();
+        assert!(err.is::<ParseError>());
+        assert_eq!(
+            err.to_string(),
+            "\"move 3 from 1 to x\": group 3 (\"x\"): invalid digit found in string"
+        );
+        assert!(captures::<(u8,)>(&re, "stay").is_err());
+        assert!(captures_opt::<(u8,)>(&re, "stay").unwrap().is_none());
+    }
+
+    #[test]
+    fn blocks_and_lines() {
+        let text = "    [D]\n[N] [C]\n\n\nmove 1\nmove 2\r\n\r\nlast\n";
+        assert_eq!(
+            blocks(text).collect::<Vec<_>>(),
+            vec!["    [D]\n[N] [C]", "move 1\nmove 2", "last"]
+        );
+        assert_eq!(blocks("\n\n").count(), 0);
+
+        assert_eq!(lines::<u32>("1\n2\n").unwrap(), vec![1, 2]);
+        let err = lines::<u32>("1\nx\n").unwrap_err();
+        assert!(err.to_string().starts_with("line 2: \"x\": "));
+    }
+}
@@ -0,0 +1,470 @@
+//! A CPU frame buffer for drawing visualizations without a GPU, and PNG and
+//! animated GIF export.
+//!
+//! Coordinates are pixels from the top left. Anything drawn off the canvas
+//! is clipped, so shapes may hang over the edges.
+
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+use std::time::Duration;
+
+use anyhow::{bail, Context, Result};
+
+use crate::grid::Grid;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct Rgb(pub u8, pub u8, pub u8);
+
+impl Rgb {
+    pub const BLACK: Rgb = Rgb(0, 0, 0);
+    pub const WHITE: Rgb = Rgb(255, 255, 255);
+    pub const GRAY: Rgb = Rgb(128, 128, 128);
+    pub const RED: Rgb = Rgb(220, 50, 47);
+    pub const GREEN: Rgb = Rgb(0, 153, 0);
+    pub const BLUE: Rgb = Rgb(38, 139, 210);
+    pub const YELLOW: Rgb = Rgb(255, 255, 102);
+
+    /// The color `t` of the way from `self` to `other`, `t` in `0.0..=1.0`.
+    pub fn lerp(self, other: Rgb, t: f64) -> Rgb {
+        let t = t.clamp(0.0, 1.0);
+        let mix = |a: u8, b: u8| (a as f64 + (b as f64 - a as f64) * t).round() as u8;
+        Rgb(
+            mix(self.0, other.0),
+            mix(self.1, other.1),
+            mix(self.2, other.2),
+        )
+    }
+}
+
+/// A list of colors to pick from by index.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Palette(pub Vec<Rgb>);
+
+impl Palette {
+    /// Dark background, light foreground, then distinct accents.
+    pub fn default_colors() -> Self {
+        Palette(vec![
+            Rgb(15, 15, 35),
+            Rgb(204, 204, 204),
+            Rgb::YELLOW,
+            Rgb::GREEN,
+            Rgb::RED,
+            Rgb::BLUE,
+            Rgb(211, 54, 130),
+            Rgb(42, 161, 152),
+            Rgb(203, 75, 22),
+            Rgb(108, 113, 196),
+        ])
+    }
+
+    /// `steps` colors evenly spaced from `from` to `to`.
+    pub fn gradient(from: Rgb, to: Rgb, steps: usize) -> Self {
+        let last = steps.saturating_sub(1).max(1) as f64;
+        Palette((0..steps).map(|i| from.lerp(to, i as f64 / last)).collect())
+    }
+
+    /// The color at `index`, wrapping around the end, or `None` if the
+    /// palette is empty.
+    pub fn get(&self, index: usize) -> Option<Rgb> {
+        self.0.get(index.checked_rem(self.0.len())?).copied()
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Canvas {
+    width: usize,
+    height: usize,
+    pixels: Vec<Rgb>,
+}
+
+impl Canvas {
+    pub fn new(width: usize, height: usize, background: Rgb) -> Self {
+        Canvas {
+            width,
+            height,
+            pixels: vec![background; width * height],
+        }
+    }
+
+    /// A canvas with one `cell_size` square per grid cell, colored by `color`.
+    pub fn from_grid<T>(
+        grid: &Grid<T>,
+        cell_size: usize,
+        mut color: impl FnMut(&T) -> Rgb,
+    ) -> Self {
+        let mut canvas = Canvas::new(
+            grid.width() * cell_size,
+            grid.height() * cell_size,
+            Rgb::BLACK,
+        );
+        for (pos, value) in grid.iter() {
+            canvas.cell(pos.x as i32, pos.y as i32, cell_size as i32, color(value));
+        }
+        canvas
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    pub fn get(&self, x: i32, y: i32) -> Option<Rgb> {
+        self.index(x, y).map(|i| self.pixels[i])
+    }
+
+    pub fn set(&mut self, x: i32, y: i32, color: Rgb) {
+        if let Some(i) = self.index(x, y) {
+            self.pixels[i] = color;
+        }
+    }
+
+    fn index(&self, x: i32, y: i32) -> Option<usize> {
+        let (x, y) = (usize::try_from(x).ok()?, usize::try_from(y).ok()?);
+        (x < self.width && y < self.height).then_some(y * self.width + x)
+    }
+
+    pub fn fill(&mut self, color: Rgb) {
+        self.pixels.fill(color);
+    }
+
+    /// A filled rectangle with its top left corner at `(x, y)`.
+    pub fn rect(&mut self, x: i32, y: i32, width: i32, height: i32, color: Rgb) {
+        let x_range = x.max(0)..(x + width).min(self.width as i32);
+        for py in y.max(0)..(y + height).min(self.height as i32) {
+            for px in x_range.clone() {
+                self.set(px, py, color);
+            }
+        }
+    }
+
+    /// A one pixel wide rectangle outline.
+    pub fn outline(&mut self, x: i32, y: i32, width: i32, height: i32, color: Rgb) {
+        if width <= 0 || height <= 0 {
+            return;
+        }
+        let (right, bottom) = (x + width - 1, y + height - 1);
+        self.line((x, y), (right, y), color);
+        self.line((x, bottom), (right, bottom), color);
+        self.line((x, y), (x, bottom), color);
+        self.line((right, y), (right, bottom), color);
+    }
+
+    /// The square for grid cell `(col, row)` when cells are `size` pixels.
+    pub fn cell(&mut self, col: i32, row: i32, size: i32, color: Rgb) {
+        self.rect(col * size, row * size, size, size, color);
+    }
+
+    /// A line between two pixels, both included.
+    pub fn line(&mut self, from: (i32, i32), to: (i32, i32), color: Rgb) {
+        // Bresenham's algorithm, for all octants.
+        let (mut x, mut y) = from;
+        let (dx, dy) = ((to.0 - x).abs(), -(to.1 - y).abs());
+        let (sx, sy) = ((to.0 - x).signum(), (to.1 - y).signum());
+        let mut err = dx + dy;
+        loop {
+            self.set(x, y, color);
+            if (x, y) == to {
+                break;
+            }
+            let e2 = 2 * err;
+            if e2 >= dy {
+                err += dy;
+                x += sx;
+            }
+            if e2 <= dx {
+                err += dx;
+                y += sy;
+            }
+        }
+    }
+
+    /// Writes `text` in a 3x5 pixel font, each font pixel `scale` pixels
+    /// square. Letters are drawn in upper case, and characters the font
+    /// lacks as `?`. Returns the width drawn.
+    pub fn text(&mut self, x: i32, y: i32, text: &str, color: Rgb, scale: i32) -> i32 {
+        let (mut cx, mut cy, mut widest) = (x, y, 0);
+        for c in text.chars() {
+            if c == '\n' {
+                (cx, cy) = (x, cy + (GLYPH_HEIGHT + 1) * scale);
+                continue;
+            }
+            for (row, bits) in glyph(c).iter().enumerate() {
+                for (col, bit) in bits.bytes().enumerate() {
+                    if bit == b'#' {
+                        let (px, py) = (cx + col as i32 * scale, cy + row as i32 * scale);
+                        self.rect(px, py, scale, scale, color);
+                    }
+                }
+            }
+            cx += (GLYPH_WIDTH + 1) * scale;
+            widest = widest.max(cx - x - scale);
+        }
+        widest
+    }
+
+    /// The pixels as packed `r, g, b` bytes, row by row.
+    pub fn to_rgb_bytes(&self) -> Vec<u8> {
+        self.pixels
+            .iter()
+            .flat_map(|Rgb(r, g, b)| [*r, *g, *b])
+            .collect()
+    }
+
+    pub fn encode_png(&self) -> Result<Vec<u8>> {
+        let mut out = Vec::new();
+        let mut encoder = png::Encoder::new(&mut out, self.width as u32, self.height as u32);
+        encoder.set_color(png::ColorType::Rgb);
+        encoder.set_depth(png::BitDepth::Eight);
+        let mut writer = encoder.write_header()?;
+        writer.write_image_data(&self.to_rgb_bytes())?;
+        writer.finish()?;
+        Ok(out)
+    }
+
+    pub fn save_png(&self, path: &Path) -> Result<()> {
+        fs::write(path, self.encode_png()?).with_context(|| format!("writing {}", path.display()))
+    }
+}
+
+/// A sequence of equally sized frames, each shown for its own delay.
+#[derive(Debug, Clone, Default)]
+pub struct Animation {
+    frames: Vec<(Canvas, Duration)>,
+    /// Play once instead of looping.
+    pub once: bool,
+}
+
+impl Animation {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn push(&mut self, frame: Canvas, delay: Duration) {
+        self.frames.push((frame, delay));
+    }
+
+    pub fn len(&self) -> usize {
+        self.frames.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.frames.is_empty()
+    }
+
+    /// Encodes an animated GIF. Frames with at most 256 colors between them
+    /// share one exact palette; otherwise each frame is quantized.
+    pub fn encode_gif(&self) -> Result<Vec<u8>> {
+        let (first, _) = match self.frames.first() {
+            Some(frame) => frame,
+            None => bail!("an animation needs at least one frame"),
+        };
+        let (width, height) = (first.width, first.height);
+        if let Some((frame, _)) = self
+            .frames
+            .iter()
+            .find(|(frame, _)| (frame.width, frame.height) != (width, height))
+        {
+            bail!(
+                "frame is {}x{}, expected {}x{}",
+                frame.width,
+                frame.height,
+                width,
+                height
+            );
+        }
+        let (gif_width, gif_height) = (u16::try_from(width)?, u16::try_from(height)?);
+
+        let mut colors = HashMap::new();
+        for (frame, _) in &self.frames {
+            for pixel in &frame.pixels {
+                let next = colors.len();
+                colors.entry(*pixel).or_insert(next);
+            }
+        }
+        let shared = colors.len() <= 256;
+        let mut palette = vec![Rgb::BLACK; if shared { colors.len() } else { 0 }];
+        if shared {
+            for (color, index) in &colors {
+                palette[*index] = *color;
+            }
+        }
+        let palette_bytes: Vec<u8> = palette.iter().flat_map(|c| [c.0, c.1, c.2]).collect();
+
+        let mut out = Vec::new();
+        {
+            let mut encoder = gif::Encoder::new(&mut out, gif_width, gif_height, &palette_bytes)?;
+            if !self.once {
+                encoder.set_repeat(gif::Repeat::Infinite)?;
+            }
+            for (frame, delay) in &self.frames {
+                let mut gif_frame = if shared {
+                    let indices: Vec<u8> = frame.pixels.iter().map(|c| colors[c] as u8).collect();
+                    gif::Frame::from_indexed_pixels(gif_width, gif_height, &indices, None)
+                } else {
+                    gif::Frame::from_rgb_speed(gif_width, gif_height, &frame.to_rgb_bytes(), 10)
+                };
+                // GIF delays are in hundredths of a second.
+                gif_frame.delay = u16::try_from(delay.as_millis() / 10).unwrap_or(u16::MAX);
+                encoder.write_frame(&gif_frame)?;
+            }
+        }
+        Ok(out)
+    }
+
+    pub fn save_gif(&self, path: &Path) -> Result<()> {
+        fs::write(path, self.encode_gif()?).with_context(|| format!("writing {}", path.display()))
+    }
+}
+
+const GLYPH_WIDTH: i32 = 3;
+const GLYPH_HEIGHT: i32 = 5;
+
+fn glyph(c: char) -> [&'static str; 5] {
+    match c.to_ascii_uppercase() {
+        ' ' => ["...", "...", "...", "...", "..."],
+        '0' => ["###", "#.#", "#.#", "#.#", "###"],
+        '1' => [".#.", "##.", ".#.", ".#.", "###"],
+        '2' => ["###", "..#", "###", "#..", "###"],
+        '3' => ["###", "..#", ".##", "..#", "###"],
+        '4' => ["#.#", "#.#", "###", "..#", "..#"],
+        '5' => ["###", "#..", "###", "..#", "###"],
+        '6' => ["###", "#..", "###", "#.#", "###"],
+        '7' => ["###", "..#", "..#", ".#.", ".#."],
+        '8' => ["###", "#.#", "###", "#.#", "###"],
+        '9' => ["###", "#.#", "###", "..#", "###"],
+        'A' => [".#.", "#.#", "###", "#.#", "#.#"],
+        'B' => ["##.", "#.#", "##.", "#.#", "##."],
+        'C' => [".##", "#..", "#..", "#..", ".##"],
+        'D' => ["##.", "#.#", "#.#", "#.#", "##."],
+        'E' => ["###", "#..", "##.", "#..", "###"],
+        'F' => ["###", "#..", "##.", "#..", "#.."],
+        'G' => [".##", "#..", "#.#", "#.#", ".##"],
+        'H' => ["#.#", "#.#", "###", "#.#", "#.#"],
+        'I' => ["###", ".#.", ".#.", ".#.", "###"],
+        'J' => ["..#", "..#", "..#", "#.#", ".#."],
+        'K' => ["#.#", "#.#", "##.", "#.#", "#.#"],
+        'L' => ["#..", "#..", "#..", "#..", "###"],
+        'M' => ["#.#", "###", "###", "#.#", "#.#"],
+        'N' => ["##.", "#.#", "#.#", "#.#", "#.#"],
+        'O' => [".#.", "#.#", "#.#", "#.#", ".#."],
+        'P' => ["##.", "#.#", "##.", "#..", "#.."],
+        'Q' => [".#.", "#.#", "#.#", "##.", ".##"],
+        'R' => ["##.", "#.#", "##.", "#.#", "#.#"],
+        'S' => [".##", "#..", ".#.", "..#", "##."],
+        'T' => ["###", ".#.", ".#.", ".#.", ".#."],
+        'U' => ["#.#", "#.#", "#.#", "#.#", "###"],
+        'V' => ["#.#", "#.#", "#.#", "#.#", ".#."],
+        'W' => ["#.#", "#.#", "###", "###", "#.#"],
+        'X' => ["#.#", "#.#", ".#.", "#.#", "#.#"],
+        'Y' => ["#.#", "#.#", ".#.", ".#.", ".#."],
+        'Z' => ["###", "..#", ".#.", "#..", "###"],
+        '-' => ["...", "...", "###", "...", "..."],
+        '+' => ["...", ".#.", "###", ".#.", "..."],
+        '=' => ["...", "###", "...", "###", "..."],
+        '.' => ["...", "...", "...", "...", ".#."],
+        ',' => ["...", "...", "...", ".#.", "#.."],
+        ':' => ["...", ".#.", "...", ".#.", "..."],
+        '!' => [".#.", ".#.", ".#.", "...", ".#."],
+        '/' => ["..#", "..#", ".#.", "#..", "#.."],
+        '#' => ["#.#", "###", "#.#", "###", "#.#"],
+        '(' => [".#.", "#..", "#..", "#..", ".#."],
+        ')' => [".#.", "..#", "..#", "..#", ".#."],
+        '[' => ["##.", "#..", "#..", "#..", "##."],
+        ']' => [".##", "..#", "..#", "..#", ".##"],
+        _ => ["##.", "..#", ".#.", "...", ".#."],
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// The canvas as text, `#` for pixels of `color` and `.` otherwise.
+    fn mask(canvas: &Canvas, color: Rgb) -> String {
+        let mut out = String::new();
+        for y in 0..canvas.height() as i32 {
+            for x in 0..canvas.width() as i32 {
+                out.push(if canvas.get(x, y) == Some(color) {
+                    '#'
+                } else {
+                    '.'
+                });
+            }
+            out.push('\n');
+        }
+        out
+    }
+
+    #[test]
+    fn shapes_and_text() {
+        let mut canvas = Canvas::new(5, 4, Rgb::BLACK);
+        canvas.rect(-1, 2, 3, 5, Rgb::RED);
+        canvas.line((0, 0), (4, 2), Rgb::WHITE);
+        assert_eq!(mask(&canvas, Rgb::RED), ".....\n.....\n##...\n##...\n");
+        assert_eq!(mask(&canvas, Rgb::WHITE), "#....\n.##..\n...##\n.....\n");
+
+        let mut canvas = Canvas::new(8, 5, Rgb::BLACK);
+        assert_eq!(canvas.text(0, 0, "1a", Rgb::WHITE, 1), 7);
+        assert_eq!(
+            mask(&canvas, Rgb::WHITE),
+            ".#...#..\n##..#.#.\n.#..###.\n.#..#.#.\n###.#.#.\n"
+        );
+
+        let grid = Grid::parse("#.\n.#\n", |c| Some(c == '#')).unwrap();
+        let canvas = Canvas::from_grid(&grid, 2, |on| if *on { Rgb::GREEN } else { Rgb::BLACK });
+        assert_eq!(mask(&canvas, Rgb::GREEN), "##..\n##..\n..##\n..##\n");
+        assert_eq!(
+            Palette::gradient(Rgb::BLACK, Rgb::WHITE, 3).get(1),
+            Some(Rgb(128, 128, 128))
+        );
+        assert_eq!(Palette::default_colors().get(11), Some(Rgb(204, 204, 204)));
+        assert_eq!(Palette(Vec::new()).get(0), None);
+        assert_eq!(Palette::gradient(Rgb::BLACK, Rgb::WHITE, 0).get(3), None);
+    }
+
+    #[test]
+    fn png_round_trip() {
+        let mut canvas = Canvas::new(3, 2, Rgb::BLUE);
+        canvas.set(2, 1, Rgb::YELLOW);
+        let bytes = canvas.encode_png().unwrap();
+
+        let decoder = png::Decoder::new(bytes.as_slice());
+        let mut reader = decoder.read_info().unwrap();
+        let mut pixels = vec![0; reader.output_buffer_size()];
+        let info = reader.next_frame(&mut pixels).unwrap();
+        assert_eq!((info.width, info.height), (3, 2));
+        assert_eq!(&pixels[..3], &[38, 139, 210]);
+        assert_eq!(&pixels[15..18], &[255, 255, 102]);
+    }
+
+    #[test]
+    fn gif_frames() {
+        let mut animation = Animation::new();
+        for i in 0..3 {
+            let mut frame = Canvas::new(4, 4, Rgb::BLACK);
+            frame.set(i, i, Rgb::WHITE);
+            animation.push(frame, Duration::from_millis(200));
+        }
+        let bytes = animation.encode_gif().unwrap();
+
+        let mut options = gif::DecodeOptions::new();
+        options.set_color_output(gif::ColorOutput::RGBA);
+        let mut decoder = options.read_info(bytes.as_slice()).unwrap();
+        let mut frames = 0;
+        while let Some(frame) = decoder.read_next_frame().unwrap() {
+            assert_eq!(frame.delay, 20);
+            let lit = (frames * 4 + frames) * 4;
+            assert_eq!(&frame.buffer[lit..lit + 3], &[255, 255, 255]);
+            frames += 1;
+        }
+        assert_eq!(frames, 3);
+
+        animation.push(Canvas::new(2, 2, Rgb::BLACK), Duration::ZERO);
+        assert!(animation.encode_gif().is_err());
+        assert!(Animation::new().encode_gif().is_err());
+    }
+}
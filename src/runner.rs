@@ -0,0 +1,187 @@
+use std::panic::{self, AssertUnwindSafe};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{mpsc, Arc};
+use std::thread;
+use std::time::Duration;
+
+use anyhow::{anyhow, Result};
+
+use crate::args::Opt;
+use crate::days;
+use crate::error::{self, Error};
+
+/// Cooperative cancellation flag shared between the runner and a solver.
+/// Long-running loops should call [`Cancel::check`] so a timed out solver
+/// stops instead of spinning in the background.
+#[derive(Debug, Clone, Default)]
+pub struct Cancel(Arc<AtomicBool>);
+
+impl Cancel {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn cancel(&self) {
+        self.0.store(true, Ordering::Relaxed);
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.0.load(Ordering::Relaxed)
+    }
+
+    /// Fails with [`Cancelled`] once the runner has given up on this solver.
+    pub fn check(&self) -> Result<(), Cancelled> {
+        if self.is_cancelled() {
+            Err(Cancelled)
+        } else {
+            Ok(())
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Cancelled;
+
+impl std::fmt::Display for Cancelled {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "cancelled")
+    }
+}
+
+impl std::error::Error for Cancelled {}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TimedOut(pub Duration);
+
+impl std::fmt::Display for TimedOut {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "timed out after {:?}", self.0)
+    }
+}
+
+impl std::error::Error for TimedOut {}
+
+/// Runs `solve` to completion, or on a worker thread with a deadline when
+/// `timeout` is set. On timeout the solver is cancelled and left to wind
+/// down on its own; the caller gets [`TimedOut`] right away. A panicking
+/// solver is reported as an error either way.
+pub fn run_part<T, F>(timeout: Option<Duration>, solve: F) -> Result<T>
+where
+    T: Send + 'static,
+    F: FnOnce(&Cancel) -> Result<T> + Send + 'static,
+{
+    let cancel = Cancel::new();
+    let timeout = match timeout {
+        Some(timeout) => timeout,
+        None => {
+            return panic::catch_unwind(AssertUnwindSafe(|| solve(&cancel)))
+                .unwrap_or_else(|_| Err(anyhow!("solver panicked")))
+        }
+    };
+
+    let (tx, rx) = mpsc::channel();
+    let worker_cancel = cancel.clone();
+    thread::Builder::new()
+        .name("solver".to_owned())
+        .spawn(move || {
+            let _ = tx.send(solve(&worker_cancel));
+        })?;
+
+    match rx.recv_timeout(timeout) {
+        Ok(result) => result,
+        Err(mpsc::RecvTimeoutError::Timeout) => {
+            cancel.cancel();
+            Err(TimedOut(timeout).into())
+        }
+        Err(mpsc::RecvTimeoutError::Disconnected) => Err(anyhow!("solver panicked")),
+    }
+}
+
+/// Runs the parts of `day` of `year` selected by `opt` on its input file and
+/// prints the solutions. On failure prints the error and exits with its
+/// category's code.
+pub fn run(year: u16, day: u8, opt: &Opt) {
+    report(solve(year, day, opt))
+}
+
+/// Prints the solutions by part, or the error, exiting with its category's
+/// code.
+pub fn report(answers: Result<Vec<(u8, String)>, Error>) {
+    match answers {
+        Ok(answers) => {
+            for (part, answer) in answers {
+                println!("Solution [{}]: {}", part, answer);
+            }
+        }
+        Err(err) => {
+            eprintln!("error: {}", err);
+            std::process::exit(err.exit_code());
+        }
+    }
+}
+
+fn solve(year: u16, day: u8, opt: &Opt) -> Result<Vec<(u8, String)>, Error> {
+    let info = days::require(year, day, opt.backend())?;
+    let timeout = opt.timeout;
+
+    let content = error::read_input(&opt.input_path(year, day))?;
+    let answers = if opt.both {
+        let both = info.both;
+        run_part(timeout, move |cancel| {
+            let (part1, part2) = both(&content, cancel)?;
+            Ok(vec![(1, part1), (2, part2)])
+        })
+    } else {
+        let (part, solve) = if opt.part2 {
+            (2, info.part2)
+        } else {
+            (1, info.part1)
+        };
+        run_part(timeout, move |cancel| {
+            Ok(vec![(part, solve(&content, cancel)?)])
+        })
+    };
+    answers.map_err(Error::from_solver)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn finishes_in_time() {
+        let answer = run_part(Some(Duration::from_secs(10)), |_| Ok(42)).unwrap();
+        assert_eq!(answer, 42);
+        assert_eq!(run_part(None, |_| Ok("inline")).unwrap(), "inline");
+    }
+
+    #[test]
+    fn spinning_solver_is_cancelled() {
+        let (tx, rx) = mpsc::channel();
+        let err = run_part(Some(Duration::from_millis(20)), move |cancel| {
+            let result = loop {
+                if let Err(err) = cancel.check() {
+                    break err;
+                }
+                thread::yield_now();
+            };
+            tx.send(result).unwrap();
+            Err::<(), _>(result.into())
+        })
+        .unwrap_err();
+
+        assert_eq!(
+            err.downcast_ref(),
+            Some(&TimedOut(Duration::from_millis(20)))
+        );
+        assert_eq!(rx.recv_timeout(Duration::from_secs(10)), Ok(Cancelled));
+    }
+
+    #[test]
+    fn panicking_solver() {
+        let err = run_part::<(), _>(Some(Duration::from_secs(10)), |_| panic!("boom"));
+        assert_eq!(err.unwrap_err().to_string(), "solver panicked");
+        let err = run_part::<(), _>(None, |_| panic!("boom"));
+        assert_eq!(err.unwrap_err().to_string(), "solver panicked");
+    }
+}
@@ -0,0 +1,326 @@
+//! Breadth-first, Dijkstra and A* search over implicit graphs and grids.
+//!
+//! A graph is given by a start node and a function listing a node's
+//! neighbors, with a cost per edge for the weighted searches. Every search
+//! stops at the first node `is_goal` accepts, or explores everything it can
+//! reach if there is none, and returns a [`Search`] to read the results from.
+
+use std::cmp::Ordering;
+use std::collections::{BinaryHeap, HashMap, HashSet, VecDeque};
+use std::hash::Hash;
+use std::ops::Add;
+
+use crate::grid::{Grid, Pos, Step};
+
+/// What a search found: the best known distance to every node it reached,
+/// how it got there, and which nodes it expanded.
+#[derive(Debug, Clone)]
+pub struct Search<N, C> {
+    distances: HashMap<N, C>,
+    parents: HashMap<N, N>,
+    visited: HashSet<N>,
+    goal: Option<N>,
+}
+
+impl<N: Clone + Eq + Hash, C: Copy> Search<N, C> {
+    fn new(start: N, zero: C) -> Self {
+        Search {
+            distances: HashMap::from([(start, zero)]),
+            parents: HashMap::new(),
+            visited: HashSet::new(),
+            goal: None,
+        }
+    }
+
+    /// The goal the search stopped at, if it found one.
+    pub fn goal(&self) -> Option<&N> {
+        self.goal.as_ref()
+    }
+
+    /// The cost of the best path found to `node`. Final for every visited
+    /// node, and for every reached node once the search has run out.
+    pub fn distance(&self, node: &N) -> Option<C> {
+        self.distances.get(node).copied()
+    }
+
+    pub fn distances(&self) -> &HashMap<N, C> {
+        &self.distances
+    }
+
+    /// The nodes that were expanded, goal included.
+    pub fn visited(&self) -> &HashSet<N> {
+        &self.visited
+    }
+
+    /// The best path found from the start to `node`, both included.
+    pub fn path_to(&self, node: &N) -> Option<Vec<N>> {
+        if !self.distances.contains_key(node) {
+            return None;
+        }
+        let mut path = vec![node.clone()];
+        while let Some(parent) = self.parents.get(path.last().unwrap()) {
+            path.push(parent.clone());
+        }
+        path.reverse();
+        Some(path)
+    }
+
+    /// The path to the goal, if one was found.
+    pub fn path(&self) -> Option<Vec<N>> {
+        self.path_to(self.goal.as_ref()?)
+    }
+}
+
+/// Unweighted search, so distances count edges.
+pub fn bfs<N, I>(
+    start: N,
+    mut neighbors: impl FnMut(&N) -> I,
+    mut is_goal: impl FnMut(&N) -> bool,
+) -> Search<N, usize>
+where
+    N: Clone + Eq + Hash,
+    I: IntoIterator<Item = N>,
+{
+    let mut search = Search::new(start.clone(), 0);
+    let mut queue = VecDeque::from([start]);
+    while let Some(node) = queue.pop_front() {
+        search.visited.insert(node.clone());
+        if is_goal(&node) {
+            search.goal = Some(node);
+            break;
+        }
+        let next_distance = search.distances[&node] + 1;
+        for next in neighbors(&node) {
+            if !search.distances.contains_key(&next) {
+                search.distances.insert(next.clone(), next_distance);
+                search.parents.insert(next.clone(), node.clone());
+                queue.push_back(next);
+            }
+        }
+    }
+    search
+}
+
+/// Search with non-negative edge costs; `neighbors` yields `(node, cost)`.
+pub fn dijkstra<N, C, I>(
+    start: N,
+    neighbors: impl FnMut(&N) -> I,
+    is_goal: impl FnMut(&N) -> bool,
+) -> Search<N, C>
+where
+    N: Clone + Eq + Hash,
+    C: Copy + Ord + Default + Add<Output = C>,
+    I: IntoIterator<Item = (N, C)>,
+{
+    astar(start, neighbors, |_| C::default(), is_goal)
+}
+
+/// [`dijkstra`] guided by `heuristic`. Nodes are never reopened once settled,
+/// so for the path found to be the cheapest the heuristic must be consistent
+/// (monotone): it never drops by more than the cost of an edge, and is 0 at a
+/// goal. Merely admissible heuristics can return a costlier path.
+pub fn astar<N, C, I>(
+    start: N,
+    mut neighbors: impl FnMut(&N) -> I,
+    mut heuristic: impl FnMut(&N) -> C,
+    mut is_goal: impl FnMut(&N) -> bool,
+) -> Search<N, C>
+where
+    N: Clone + Eq + Hash,
+    C: Copy + Ord + Default + Add<Output = C>,
+    I: IntoIterator<Item = (N, C)>,
+{
+    let mut search = Search::new(start.clone(), C::default());
+    let mut queue = BinaryHeap::from([Queued {
+        priority: heuristic(&start),
+        node: start,
+    }]);
+    while let Some(Queued { node, .. }) = queue.pop() {
+        // A node can be queued again after a cheaper path turns up; only
+        // the first time it's popped counts.
+        if !search.visited.insert(node.clone()) {
+            continue;
+        }
+        if is_goal(&node) {
+            search.goal = Some(node);
+            break;
+        }
+        let distance = search.distances[&node];
+        for (next, cost) in neighbors(&node) {
+            let next_distance = distance + cost;
+            if search
+                .distances
+                .get(&next)
+                .is_none_or(|known| next_distance < *known)
+            {
+                search.distances.insert(next.clone(), next_distance);
+                search.parents.insert(next.clone(), node.clone());
+                queue.push(Queued {
+                    priority: next_distance + heuristic(&next),
+                    node: next,
+                });
+            }
+        }
+    }
+    search
+}
+
+/// A heap entry ordered so the lowest priority pops first.
+struct Queued<N, C> {
+    priority: C,
+    node: N,
+}
+
+impl<N, C: Ord> Ord for Queued<N, C> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        other.priority.cmp(&self.priority)
+    }
+}
+
+impl<N, C: Ord> PartialOrd for Queued<N, C> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<N, C: Ord> PartialEq for Queued<N, C> {
+    fn eq(&self, other: &Self) -> bool {
+        self.priority == other.priority
+    }
+}
+
+impl<N, C: Ord> Eq for Queued<N, C> {}
+
+/// Searches over a grid's cells, moving by `steps`, e.g. `&Direction::ALL`.
+impl<T> Grid<T> {
+    /// Breadth-first from `start`, moving only where `can_move(from, to)`.
+    pub fn bfs<S: Copy + Into<Step>>(
+        &self,
+        start: Pos,
+        steps: &[S],
+        can_move: impl Fn(Pos, Pos) -> bool,
+        is_goal: impl FnMut(&Pos) -> bool,
+    ) -> Search<Pos, usize> {
+        let can_move = &can_move;
+        bfs(
+            start,
+            |&pos| {
+                self.neighbors(pos, steps.iter().copied())
+                    .filter(move |&next| can_move(pos, next))
+            },
+            is_goal,
+        )
+    }
+
+    /// Cheapest paths from `start`, where `cost(from, to)` prices each move
+    /// and `None` forbids it.
+    pub fn dijkstra<S, C>(
+        &self,
+        start: Pos,
+        steps: &[S],
+        cost: impl Fn(Pos, Pos) -> Option<C>,
+        is_goal: impl FnMut(&Pos) -> bool,
+    ) -> Search<Pos, C>
+    where
+        S: Copy + Into<Step>,
+        C: Copy + Ord + Default + Add<Output = C>,
+    {
+        self.astar(start, steps, cost, |_| C::default(), is_goal)
+    }
+
+    /// [`Grid::dijkstra`] guided by `heuristic`; see [`astar`].
+    pub fn astar<S, C>(
+        &self,
+        start: Pos,
+        steps: &[S],
+        cost: impl Fn(Pos, Pos) -> Option<C>,
+        heuristic: impl FnMut(&Pos) -> C,
+        is_goal: impl FnMut(&Pos) -> bool,
+    ) -> Search<Pos, C>
+    where
+        S: Copy + Into<Step>,
+        C: Copy + Ord + Default + Add<Output = C>,
+    {
+        let cost = &cost;
+        astar(
+            start,
+            |&pos| {
+                self.neighbors(pos, steps.iter().copied())
+                    .filter_map(move |next| Some((next, cost(pos, next)?)))
+            },
+            heuristic,
+            is_goal,
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::geom::{Direction, Point2};
+
+    const MAZE: &str = "\
+S.#.....
+.##.###.
+....#.E.
+";
+
+    fn maze() -> (Grid<char>, Pos, Pos) {
+        let grid = Grid::parse(MAZE, Some).unwrap();
+        let find = |c| grid.iter().find(|(_, cell)| **cell == c).unwrap().0;
+        let (start, end) = (find('S'), find('E'));
+        (grid, start, end)
+    }
+
+    #[test]
+    fn implicit_bfs() {
+        // Fewest "+1" or "*2" operations to turn 1 into 10.
+        let search = bfs(1u32, |&n| [n + 1, n * 2], |&n| n == 10);
+        assert_eq!(search.goal(), Some(&10));
+        assert_eq!(search.distance(&10), Some(4));
+        assert_eq!(search.path().unwrap(), vec![1, 2, 4, 5, 10]);
+    }
+
+    #[test]
+    fn grid_bfs() {
+        let (grid, start, end) = maze();
+        let open = |_, to: Pos| grid[to] != '#';
+        let search = grid.bfs(start, &Direction::ALL, open, |pos| *pos == end);
+        let path = search.path().unwrap();
+        assert_eq!(path.len() - 1, search.distance(&end).unwrap());
+        assert_eq!(search.distance(&end), Some(14));
+        assert_eq!(path.first(), Some(&start));
+        assert!(path.iter().all(|pos| grid[*pos] != '#'));
+
+        // Exploring everything reaches each open cell once.
+        let all = grid.bfs(start, &Direction::ALL, open, |_| false);
+        assert_eq!(all.goal(), None);
+        assert_eq!(all.visited().len(), 17);
+        assert_eq!(all.distances().len(), 17);
+        assert_eq!(all.path_to(&Point2::new(2, 0)), None);
+    }
+
+    #[test]
+    fn weighted() {
+        let grid = Grid::parse("1163\n1381\n2136\n", |c| c.to_digit(10)).unwrap();
+        let (start, end) = (Point2::new(0, 0), Point2::new(3, 2));
+        let cost = |_, to: Pos| Some(grid[to]);
+        let dijkstra = grid.dijkstra(start, &Direction::ALL, cost, |pos| *pos == end);
+        assert_eq!(dijkstra.distance(&end), Some(13));
+
+        let manhattan = |pos: &Pos| pos.manhattan(end) as u32;
+        let astar = grid.astar(start, &Direction::ALL, cost, manhattan, |pos| *pos == end);
+        assert_eq!(astar.distance(&end), Some(13));
+        assert_eq!(
+            astar
+                .path()
+                .unwrap()
+                .iter()
+                .skip(1)
+                .map(|pos| grid[*pos])
+                .sum::<u32>(),
+            13
+        );
+        assert!(astar.visited().len() <= dijkstra.visited().len());
+    }
+}
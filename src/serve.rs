@@ -0,0 +1,338 @@
+use std::io::{BufRead, BufReader, Read, Take, Write};
+use std::net::{TcpListener, TcpStream};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::thread;
+use std::time::{Duration, Instant};
+
+use anyhow::{anyhow, Result};
+
+use crate::days;
+use crate::error::Error;
+use crate::runner;
+
+/// Largest request body accepted, comfortably above any puzzle input.
+const MAX_BODY: usize = 16 * 1024 * 1024;
+
+/// Largest request line and headers accepted, together.
+const MAX_HEAD: u64 = 64 * 1024;
+
+/// How long a client may leave a request half sent before it's dropped.
+const READ_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Connections handled at once; any more are turned away with a 503.
+const MAX_CONNECTIONS: usize = 64;
+
+/// The request line and headers ran past `MAX_HEAD`.
+#[derive(Debug, thiserror::Error)]
+#[error("request line and headers are over {} bytes", MAX_HEAD)]
+struct HeadTooLarge;
+
+/// Counts a connection as open until it's dropped.
+struct Slot(Arc<AtomicUsize>);
+
+impl Drop for Slot {
+    fn drop(&mut self) {
+        self.0.fetch_sub(1, Ordering::AcqRel);
+    }
+}
+
+struct Response {
+    status: u16,
+    body: String,
+}
+
+impl Response {
+    fn error(status: u16, message: impl std::fmt::Display) -> Self {
+        Response {
+            status,
+            body: format!("{{\"error\":{}}}", json_string(&message.to_string())),
+        }
+    }
+}
+
+fn reason(status: u16) -> &'static str {
+    match status {
+        200 => "OK",
+        400 => "Bad Request",
+        404 => "Not Found",
+        405 => "Method Not Allowed",
+        422 => "Unprocessable Entity",
+        431 => "Request Header Fields Too Large",
+        503 => "Service Unavailable",
+        504 => "Gateway Timeout",
+        _ => "Internal Server Error",
+    }
+}
+
+/// Serves `POST /{year}/day/{n}/part/{p}` on `listener` until it fails, one
+/// thread per connection, up to `MAX_CONNECTIONS` at once. Leaving off the
+/// year means the latest one. The request body is the puzzle input; the
+/// response is a JSON object with the answer and how long the solver took.
+pub fn serve(listener: TcpListener, timeout: Option<Duration>) -> Result<()> {
+    let open = Arc::new(AtomicUsize::new(0));
+    for stream in listener.incoming() {
+        let stream = stream?;
+        // Taken before the check, so a turned-away connection gives its count
+        // back when the slot drops too.
+        let slot = Slot(Arc::clone(&open));
+        if open.fetch_add(1, Ordering::AcqRel) >= MAX_CONNECTIONS {
+            let busy = Response::error(503, "too many connections, try again later");
+            if let Err(err) = respond(&stream, &busy) {
+                eprintln!("connection error: {:#}", err);
+            }
+            continue;
+        }
+        thread::spawn(move || {
+            if let Err(err) = handle(&stream, timeout) {
+                eprintln!("connection error: {:#}", err);
+            }
+            // Free the slot before the client sees the connection close.
+            drop(slot);
+        });
+    }
+    Ok(())
+}
+
+fn handle(stream: &TcpStream, timeout: Option<Duration>) -> Result<()> {
+    stream.set_read_timeout(Some(READ_TIMEOUT))?;
+    let mut reader = BufReader::new(stream.try_clone()?);
+    let response = match read_request(&mut reader) {
+        Ok((method, path, body)) => route(&method, &path, body, timeout),
+        Err(err) if err.is::<HeadTooLarge>() => Response::error(431, err),
+        Err(err) => Response::error(400, err),
+    };
+    respond(stream, &response)
+}
+
+fn respond(mut stream: &TcpStream, response: &Response) -> Result<()> {
+    write!(
+        stream,
+        "HTTP/1.1 {} {}\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+        response.status,
+        reason(response.status),
+        response.body.len(),
+        response.body
+    )?;
+    stream.flush()?;
+    Ok(())
+}
+
+fn read_request(reader: &mut impl BufRead) -> Result<(String, String, Vec<u8>)> {
+    let mut head = reader.by_ref().take(MAX_HEAD);
+    let request_line = head_line(&mut head)?;
+    let mut parts = request_line.split_whitespace();
+    let method = parts.next().ok_or(anyhow!("empty request"))?.to_owned();
+    let path = parts
+        .next()
+        .ok_or(anyhow!("missing request path"))?
+        .to_owned();
+
+    let mut content_length = 0;
+    loop {
+        let header = head_line(&mut head)?;
+        if header.trim_end().is_empty() {
+            break;
+        }
+        if let Some((name, value)) = header.split_once(':') {
+            if name.eq_ignore_ascii_case("content-length") {
+                content_length = value.trim().parse()?;
+            }
+        }
+    }
+    if content_length > MAX_BODY {
+        return Err(anyhow!("body of {} bytes is too large", content_length));
+    }
+
+    let mut body = vec![0; content_length];
+    reader.read_exact(&mut body)?;
+    Ok((method, path, body))
+}
+
+/// Reads one line of the request line and headers, empty at the end of the
+/// stream.
+fn head_line(head: &mut Take<impl BufRead>) -> Result<String> {
+    let mut line = String::new();
+    head.read_line(&mut line)?;
+    if !line.ends_with('\n') && head.limit() == 0 {
+        return Err(HeadTooLarge.into());
+    }
+    Ok(line)
+}
+
+fn route(method: &str, path: &str, body: Vec<u8>, timeout: Option<Duration>) -> Response {
+    let segments = path.trim_matches('/').split('/').collect::<Vec<_>>();
+    let (year, day, part) = match segments.as_slice() {
+        ["day", day, "part", part] => (Ok(days::LATEST_YEAR), day, part),
+        [year, "day", day, "part", part] => (year.parse::<u16>(), day, part),
+        _ => return Response::error(404, format!("no route for {}", path)),
+    };
+    let (year, day, part) = match (year, day.parse::<u8>(), part.parse::<u8>()) {
+        (Ok(year), Ok(day), Ok(part)) => (year, day, part),
+        _ => return Response::error(404, format!("no route for {}", path)),
+    };
+    if method != "POST" {
+        return Response::error(405, "solvers only accept POST");
+    }
+    let solve = match days::solver(year, day, part) {
+        Some(solve) => solve,
+        None => {
+            return Response::error(
+                404,
+                format!("no solver for {} day {} part {}", year, day, part),
+            )
+        }
+    };
+    let input = match String::from_utf8(body) {
+        Ok(input) => input,
+        Err(_) => return Response::error(400, "input is not valid UTF-8"),
+    };
+
+    let start = Instant::now();
+    let solution = runner::run_part(timeout, move |cancel| solve(&input, cancel));
+    let elapsed = start.elapsed();
+
+    match solution {
+        Ok(answer) => Response {
+            status: 200,
+            body: format!(
+                "{{\"year\":{},\"day\":{},\"part\":{},\"answer\":{},\"elapsed_ms\":{:.3}}}",
+                year,
+                day,
+                part,
+                json_string(&answer),
+                elapsed.as_secs_f64() * 1000.0
+            ),
+        },
+        Err(err) => match Error::from_solver(err) {
+            err @ Error::TimedOut(_) => Response::error(504, err),
+            err @ Error::Parse(_) => Response::error(422, err),
+            err => Response::error(500, err),
+        },
+    }
+}
+
+fn json_string(value: &str) -> String {
+    let mut out = String::with_capacity(value.len() + 2);
+    out.push('"');
+    for c in value.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            '\t' => out.push_str("\\t"),
+            c if (c as u32) < 0x20 => out.push_str(&format!("\\u{:04x}", c as u32)),
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Read;
+
+    fn start() -> std::net::SocketAddr {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = listener.local_addr().unwrap();
+        thread::spawn(move || serve(listener, Some(Duration::from_secs(10))));
+        addr
+    }
+
+    fn request(addr: std::net::SocketAddr, method: &str, path: &str, body: &str) -> (u16, String) {
+        send(
+            addr,
+            &format!(
+                "{} {} HTTP/1.1\r\nHost: localhost\r\nContent-Length: {}\r\n\r\n{}",
+                method,
+                path,
+                body.len(),
+                body
+            ),
+        )
+    }
+
+    fn send(addr: std::net::SocketAddr, request: &str) -> (u16, String) {
+        let mut stream = TcpStream::connect(addr).unwrap();
+        stream.write_all(request.as_bytes()).unwrap();
+        read_response(stream)
+    }
+
+    fn read_response(mut stream: TcpStream) -> (u16, String) {
+        let mut response = String::new();
+        stream.read_to_string(&mut response).unwrap();
+        let status = response[9..12].parse().unwrap();
+        let body = response.split("\r\n\r\n").nth(1).unwrap().to_owned();
+        (status, body)
+    }
+
+    #[test]
+    fn solves_example() {
+        let addr = start();
+        let input = include_str!("../inputs/2022/day5_example.txt");
+        let (status, body) = request(addr, "POST", "/day/5/part/2", input);
+        assert_eq!(status, 200);
+        assert!(
+            body.starts_with(
+                "{\"year\":2022,\"day\":5,\"part\":2,\"answer\":\"MCD\",\"elapsed_ms\":"
+            ),
+            "{}",
+            body
+        );
+        let (status, body) = request(addr, "POST", "/2022/day/5/part/1", input);
+        assert_eq!(status, 200);
+        assert!(body.contains("\"answer\":\"CMZ\""), "{}", body);
+    }
+
+    #[test]
+    fn errors() {
+        let addr = start();
+        assert_eq!(request(addr, "GET", "/day/4/part/1", "").0, 405);
+        assert_eq!(request(addr, "POST", "/day/4/part/3", "").0, 404);
+        assert_eq!(request(addr, "POST", "/day/26/part/1", "").0, 404);
+        assert_eq!(request(addr, "POST", "/nope", "").0, 404);
+        assert_eq!(request(addr, "POST", "/2015/day/1/part/1", "").0, 404);
+        let (status, body) = request(addr, "POST", "/day/1/part/1", "12\nabc\n");
+        assert_eq!(status, 422);
+        assert!(body.contains("invalid digit"), "{}", body);
+    }
+
+    #[test]
+    fn caps_the_request_head() {
+        let addr = start();
+        let line = "a".repeat(MAX_HEAD as usize);
+        assert_eq!(send(addr, &line).0, 431);
+
+        let head = "POST /day/1/part/1 HTTP/1.1\r\nX-Padding: ";
+        let padding = "a".repeat(MAX_HEAD as usize - head.len());
+        let (status, body) = send(addr, &format!("{}{}", head, padding));
+        assert_eq!(status, 431);
+        assert!(body.contains("over 65536 bytes"), "{}", body);
+    }
+
+    #[test]
+    fn sheds_connections_over_the_limit() {
+        let addr = start();
+        // Each one holds a handler until it sends a request.
+        let idle: Vec<_> = (0..MAX_CONNECTIONS)
+            .map(|_| TcpStream::connect(addr).unwrap())
+            .collect();
+        let (status, _) = read_response(TcpStream::connect(addr).unwrap());
+        assert_eq!(status, 503);
+
+        // Finishing one frees its slot for the next client.
+        let mut first = idle.into_iter().next().unwrap();
+        write!(first, "POST /nope HTTP/1.1\r\n\r\n").unwrap();
+        assert_eq!(read_response(first).0, 404);
+        let input = include_str!("../inputs/2022/day5_example.txt");
+        assert_eq!(request(addr, "POST", "/day/5/part/1", input).0, 200);
+    }
+
+    #[test]
+    fn escapes_json() {
+        assert_eq!(json_string("a\"b\\c\n\u{1}"), "\"a\\\"b\\\\c\\n\\u0001\"");
+    }
+}
@@ -0,0 +1,127 @@
+//! The compute shaders under `assets/shaders` and the bind group each one
+//! expects. Pipelines build their layouts from these tables, and
+//! `tests/shaders.rs` checks the tables against the WGSL source.
+
+/// How a shader sees one binding of group 0.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BindingKind {
+    /// A `texture_storage_2d<rgba8unorm, read_write>`.
+    StorageTexture,
+    /// A `var<storage>` buffer.
+    Storage { read_only: bool },
+    /// A `var<uniform>` buffer of `size` bytes.
+    Uniform { size: u64 },
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Binding {
+    pub binding: u32,
+    pub kind: BindingKind,
+}
+
+#[derive(Debug)]
+pub struct Shader {
+    /// Relative to the `assets` directory, as passed to the asset server.
+    pub path: &'static str,
+    /// The WGSL itself, built into the binary for running it without the
+    /// asset server.
+    pub source: &'static str,
+    /// The compute entry points the pipelines are created from.
+    pub entry_points: &'static [&'static str],
+    pub bindings: &'static [Binding],
+}
+
+/// Paints the day 1 calories into a texture and finds the largest elf
+/// totals.
+pub const DAY1: Shader = Shader {
+    path: "shaders/day1.wgsl",
+    source: include_str!("../assets/shaders/day1.wgsl"),
+    entry_points: &["init", "sum", "top3"],
+    bindings: &[
+        Binding {
+            binding: 0,
+            kind: BindingKind::StorageTexture,
+        },
+        Binding {
+            binding: 1,
+            kind: BindingKind::Storage { read_only: true },
+        },
+        Binding {
+            binding: 2,
+            kind: BindingKind::Storage { read_only: false },
+        },
+        Binding {
+            binding: 3,
+            kind: BindingKind::Storage { read_only: false },
+        },
+        Binding {
+            binding: 4,
+            kind: BindingKind::Uniform { size: 12 },
+        },
+        Binding {
+            binding: 5,
+            kind: BindingKind::Storage { read_only: true },
+        },
+    ],
+};
+
+/// The gradient in `examples/compute`, reporting the color under the cursor.
+pub const GRADIENT: Shader = Shader {
+    path: "shaders/compute.wgsl",
+    source: include_str!("../assets/shaders/compute.wgsl"),
+    entry_points: &["init", "update"],
+    bindings: &[
+        Binding {
+            binding: 0,
+            kind: BindingKind::StorageTexture,
+        },
+        Binding {
+            binding: 1,
+            kind: BindingKind::Storage { read_only: false },
+        },
+        Binding {
+            binding: 2,
+            kind: BindingKind::Uniform { size: 8 },
+        },
+    ],
+};
+
+/// Every shader a pipeline is built from.
+pub const SHADERS: &[&Shader] = &[&DAY1, &GRADIENT];
+
+#[cfg(feature = "gpu")]
+impl Shader {
+    /// The layout entries for group 0, visible to compute only.
+    pub fn layout_entries(&self) -> Vec<bevy::render::render_resource::BindGroupLayoutEntry> {
+        use bevy::render::render_resource::{
+            BindGroupLayoutEntry, BindingType, BufferBindingType, BufferSize, ShaderStages,
+            StorageTextureAccess, TextureFormat, TextureViewDimension,
+        };
+
+        self.bindings
+            .iter()
+            .map(|binding| BindGroupLayoutEntry {
+                binding: binding.binding,
+                visibility: ShaderStages::COMPUTE,
+                ty: match binding.kind {
+                    BindingKind::StorageTexture => BindingType::StorageTexture {
+                        access: StorageTextureAccess::ReadWrite,
+                        format: TextureFormat::Rgba8Unorm,
+                        view_dimension: TextureViewDimension::D2,
+                    },
+                    BindingKind::Storage { read_only } => BindingType::Buffer {
+                        ty: BufferBindingType::Storage { read_only },
+                        has_dynamic_offset: false,
+                        min_binding_size: None,
+                    },
+                    BindingKind::Uniform { size } => BindingType::Buffer {
+                        ty: BufferBindingType::Uniform,
+                        has_dynamic_offset: false,
+                        min_binding_size: BufferSize::new(size),
+                    },
+                },
+                count: None,
+            })
+            .collect()
+    }
+}
@@ -0,0 +1,344 @@
+//! Animations as sequences of colored character grids, and a terminal player
+//! for watching them.
+
+use std::fmt;
+use std::io::{self, Write};
+use std::time::{Duration, Instant};
+
+use anyhow::Result;
+use crossterm::event::{self, Event, KeyCode, KeyEvent};
+use crossterm::{cursor, queue, style, terminal};
+
+use crate::geom::Point2;
+use crate::grid::Grid;
+use crate::raster::{Animation, Canvas, Rgb};
+
+/// A model that can be watched as an animation.
+pub trait Visualize {
+    /// The frames in order, typically one per step of the simulation.
+    fn frames(&self) -> Box<dyn Iterator<Item = Frame> + '_>;
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Cell {
+    pub ch: char,
+    /// `None` leaves the terminal's default color.
+    pub color: Option<Rgb>,
+}
+
+impl Default for Cell {
+    fn default() -> Self {
+        Cell {
+            ch: ' ',
+            color: None,
+        }
+    }
+}
+
+/// One picture of an animation, with a line of text to show beside it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Frame {
+    pub cells: Grid<Cell>,
+    pub caption: String,
+}
+
+impl Frame {
+    pub fn new(width: usize, height: usize) -> Self {
+        Frame {
+            cells: Grid::new(width, height, Cell::default()),
+            caption: String::new(),
+        }
+    }
+
+    /// Sets one cell; positions off the frame are ignored.
+    pub fn put(&mut self, x: usize, y: usize, ch: char, color: Option<Rgb>) {
+        if let Some(cell) = self.cells.get_mut(Point2::new(x, y)) {
+            *cell = Cell { ch, color };
+        }
+    }
+
+    /// Writes `text` left to right from `(x, y)`, clipped at the edge.
+    pub fn text(&mut self, x: usize, y: usize, text: &str, color: Option<Rgb>) {
+        for (i, ch) in text.chars().enumerate() {
+            self.put(x + i, y, ch, color);
+        }
+    }
+
+    /// Draws the frame as a block of characters, one font glyph per cell.
+    /// Cells without a color use `foreground`.
+    pub fn to_canvas(&self, scale: i32, foreground: Rgb, background: Rgb) -> Canvas {
+        let (cell_width, cell_height) = (4 * scale, 6 * scale);
+        let mut canvas = Canvas::new(
+            self.cells.width() * cell_width as usize,
+            self.cells.height() * cell_height as usize,
+            background,
+        );
+        for (pos, cell) in self.cells.iter() {
+            if cell.ch != ' ' {
+                let (x, y) = (pos.x as i32 * cell_width, pos.y as i32 * cell_height);
+                let color = cell.color.unwrap_or(foreground);
+                canvas.text(x, y, &cell.ch.to_string(), color, scale);
+            }
+        }
+        canvas
+    }
+}
+
+/// The characters without colors, one line per row with trailing spaces
+/// trimmed.
+impl fmt::Display for Frame {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for row in self.cells.rows() {
+            let line: String = row.iter().map(|cell| cell.ch).collect();
+            writeln!(f, "{}", line.trim_end())?;
+        }
+        Ok(())
+    }
+}
+
+/// Draws `frames` as an animation to save as a GIF, `delay` apart.
+pub fn animate(frames: &[Frame], delay: Duration) -> Animation {
+    let mut animation = Animation::new();
+    for frame in frames {
+        let canvas = frame.to_canvas(2, Rgb(204, 204, 204), Rgb(15, 15, 35));
+        animation.push(canvas, delay);
+    }
+    animation
+}
+
+/// Playback state, separate from the terminal so it can be driven by tests.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Player {
+    frames: usize,
+    index: usize,
+    playing: bool,
+    fps: f64,
+}
+
+/// What the player was asked to do.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Control {
+    TogglePause,
+    Step(isize),
+    /// Jump to a fraction of the way through, `0.0` being the first frame.
+    Seek(f64),
+    First,
+    Last,
+    Faster,
+    Slower,
+    Quit,
+}
+
+impl Control {
+    pub fn from_key(key: KeyCode) -> Option<Control> {
+        Some(match key {
+            KeyCode::Char(' ') => Control::TogglePause,
+            KeyCode::Right | KeyCode::Char('l') => Control::Step(1),
+            KeyCode::Left | KeyCode::Char('h') => Control::Step(-1),
+            KeyCode::PageDown => Control::Step(10),
+            KeyCode::PageUp => Control::Step(-10),
+            KeyCode::Home | KeyCode::Char('g') => Control::First,
+            KeyCode::End | KeyCode::Char('G') => Control::Last,
+            KeyCode::Char('+') | KeyCode::Char(']') => Control::Faster,
+            KeyCode::Char('-') | KeyCode::Char('[') => Control::Slower,
+            KeyCode::Char(digit @ '0'..='9') => {
+                Control::Seek(digit.to_digit(10).unwrap() as f64 / 10.0)
+            }
+            KeyCode::Char('q') | KeyCode::Esc => Control::Quit,
+            _ => return None,
+        })
+    }
+}
+
+impl Player {
+    const MIN_FPS: f64 = 0.25;
+    const MAX_FPS: f64 = 960.0;
+
+    pub fn new(frames: usize, fps: f64) -> Self {
+        Player {
+            frames,
+            index: 0,
+            playing: true,
+            fps: fps.clamp(Self::MIN_FPS, Self::MAX_FPS),
+        }
+    }
+
+    pub fn index(&self) -> usize {
+        self.index
+    }
+
+    pub fn is_playing(&self) -> bool {
+        self.playing
+    }
+
+    pub fn fps(&self) -> f64 {
+        self.fps
+    }
+
+    pub fn interval(&self) -> Duration {
+        Duration::from_secs_f64(1.0 / self.fps)
+    }
+
+    fn last(&self) -> usize {
+        self.frames.saturating_sub(1)
+    }
+
+    /// Applies `control`, returning false once the player should close.
+    pub fn control(&mut self, control: Control) -> bool {
+        match control {
+            Control::TogglePause => self.playing = !self.playing,
+            Control::Step(by) => {
+                self.playing = false;
+                self.index = self.index.saturating_add_signed(by).min(self.last());
+            }
+            Control::Seek(fraction) => {
+                self.index = (self.last() as f64 * fraction.clamp(0.0, 1.0)).round() as usize
+            }
+            Control::First => self.index = 0,
+            Control::Last => self.index = self.last(),
+            Control::Faster => self.fps = (self.fps * 2.0).min(Self::MAX_FPS),
+            Control::Slower => self.fps = (self.fps / 2.0).max(Self::MIN_FPS),
+            Control::Quit => return false,
+        }
+        true
+    }
+
+    /// Moves on one frame if playing, pausing at the end.
+    pub fn tick(&mut self) {
+        if !self.playing {
+            return;
+        }
+        if self.index < self.last() {
+            self.index += 1;
+        }
+        if self.index == self.last() {
+            self.playing = false;
+        }
+    }
+
+    fn status(&self) -> String {
+        format!(
+            "frame {}/{}  {} fps  {}  [space] pause  [<-/->] step  [+/-] speed  [0-9] seek  [q] quit",
+            self.index + 1,
+            self.frames,
+            self.fps,
+            if self.playing { "playing" } else { "paused" },
+        )
+    }
+}
+
+/// Plays `frames` in the terminal until the viewer quits.
+pub fn play(frames: &[Frame], fps: f64) -> Result<()> {
+    if frames.is_empty() {
+        anyhow::bail!("nothing to play");
+    }
+    let mut stdout = io::stdout();
+    terminal::enable_raw_mode()?;
+    queue!(stdout, terminal::EnterAlternateScreen, cursor::Hide)?;
+    let result = run_player(&mut stdout, frames, fps);
+    queue!(stdout, cursor::Show, terminal::LeaveAlternateScreen)?;
+    stdout.flush()?;
+    terminal::disable_raw_mode()?;
+    result
+}
+
+fn run_player(out: &mut impl Write, frames: &[Frame], fps: f64) -> Result<()> {
+    let mut player = Player::new(frames.len(), fps);
+    let mut next_tick = Instant::now() + player.interval();
+    loop {
+        draw(out, &frames[player.index()], &player)?;
+        let timeout = next_tick.saturating_duration_since(Instant::now());
+        if event::poll(timeout)? {
+            if let Event::Key(KeyEvent { code, .. }) = event::read()? {
+                if let Some(control) = Control::from_key(code) {
+                    if !player.control(control) {
+                        return Ok(());
+                    }
+                }
+            }
+        } else {
+            player.tick();
+            next_tick = Instant::now() + player.interval();
+        }
+    }
+}
+
+fn draw(out: &mut impl Write, frame: &Frame, player: &Player) -> Result<()> {
+    queue!(
+        out,
+        cursor::MoveTo(0, 0),
+        terminal::Clear(terminal::ClearType::All)
+    )?;
+    queue!(out, style::Print(&frame.caption), cursor::MoveToNextLine(1))?;
+    for row in frame.cells.rows() {
+        for cell in row {
+            match cell.color {
+                Some(Rgb(r, g, b)) => queue!(
+                    out,
+                    style::SetForegroundColor(style::Color::Rgb { r, g, b }),
+                    style::Print(cell.ch),
+                    style::ResetColor
+                )?,
+                None => queue!(out, style::Print(cell.ch))?,
+            }
+        }
+        queue!(out, cursor::MoveToNextLine(1))?;
+    }
+    queue!(out, style::Print(player.status()))?;
+    out.flush()?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn frame_text() {
+        let mut frame = Frame::new(4, 2);
+        frame.text(1, 0, "ab", Some(Rgb::RED));
+        frame.put(3, 1, 'c', None);
+        frame.put(9, 9, 'x', None);
+        assert_eq!(frame.to_string(), " ab\n   c\n");
+        assert_eq!(frame.cells[(1, 0)].color, Some(Rgb::RED));
+
+        let canvas = frame.to_canvas(1, Rgb::WHITE, Rgb::BLACK);
+        assert_eq!((canvas.width(), canvas.height()), (16, 12));
+        assert_eq!(canvas.get(5, 0), Some(Rgb::RED));
+    }
+
+    #[test]
+    fn player_controls() {
+        let mut player = Player::new(11, 10.0);
+        player.tick();
+        assert_eq!(player.index(), 1);
+        assert!(player.control(Control::TogglePause));
+        player.tick();
+        assert_eq!(player.index(), 1);
+
+        player.control(Control::Step(-5));
+        assert_eq!(player.index(), 0);
+        player.control(Control::Seek(0.5));
+        assert_eq!(player.index(), 5);
+        player.control(Control::Last);
+        assert_eq!(player.index(), 10);
+        player.control(Control::Step(1));
+        assert_eq!(player.index(), 10);
+
+        player.control(Control::Faster);
+        assert_eq!(player.interval(), Duration::from_millis(50));
+        player.control(Control::First);
+        player.control(Control::TogglePause);
+        for _ in 0..20 {
+            player.tick();
+        }
+        assert_eq!(player.index(), 10);
+        assert!(!player.is_playing());
+
+        assert_eq!(
+            Control::from_key(KeyCode::Char('3')),
+            Some(Control::Seek(0.3))
+        );
+        assert!(!player.control(Control::from_key(KeyCode::Esc).unwrap()));
+    }
+}
@@ -0,0 +1,57 @@
+//! Runs every solver on every input listed in `inputs/answers.json` and
+//! checks the answers.
+
+use advent::days;
+use advent::inputs;
+use advent::runner::Cancel;
+
+#[test]
+fn manifest_answers() {
+    let manifest = inputs::manifest().unwrap();
+    let mut failures = Vec::new();
+    for expected in &manifest {
+        let info = days::info(expected.year, expected.day)
+            .unwrap_or_else(|| panic!("{} day {} isn't registered", expected.year, expected.day));
+        let input = std::fs::read_to_string(expected.path()).unwrap();
+        for part in [1, 2] {
+            let answer = match expected.answer(part) {
+                Some(answer) => answer,
+                None => continue,
+            };
+            let got = info.part(part).unwrap()(&input, &Cancel::new());
+            if got.as_deref().ok() != Some(answer) {
+                failures.push(format!(
+                    "{} day {} part {} on {}: expected {}, got {:?}",
+                    expected.year, expected.day, part, expected.input, answer, got
+                ));
+            }
+        }
+        if let (Some(part1), Some(part2)) = (&expected.part1, &expected.part2) {
+            let got = (info.both)(&input, &Cancel::new());
+            if got.as_ref().ok() != Some(&(part1.clone(), part2.clone())) {
+                failures.push(format!(
+                    "{} day {} both parts on {}: expected ({}, {}), got {:?}",
+                    expected.year, expected.day, expected.input, part1, part2, got
+                ));
+            }
+        }
+    }
+    assert!(failures.is_empty(), "{}", failures.join("\n"));
+}
+
+#[test]
+fn every_day_has_a_real_input() {
+    let manifest = inputs::manifest().unwrap();
+    for year in days::YEARS {
+        for info in year.days {
+            assert!(
+                manifest
+                    .iter()
+                    .any(|e| e.year == year.year && e.day == info.day && e.is_real()),
+                "{} day {} has no real input in the manifest",
+                year.year,
+                info.day
+            );
+        }
+    }
+}
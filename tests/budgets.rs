@@ -0,0 +1,117 @@
+//! Time budgets for every part on the real inputs. Only meaningful in an
+//! optimized build, so run with `cargo test --release`.
+//!
+//! Every part currently finishes well under a millisecond on a laptop; the
+//! budgets leave room for slower machines while still catching a solver that
+//! goes accidentally quadratic. Printing is cheap while the test harness
+//! captures output, so leftover `dbg!` calls are caught by reading the source
+//! instead.
+
+use std::path::Path;
+use std::time::{Duration, Instant};
+
+use advent::days;
+use advent::inputs;
+use advent::runner::Cancel;
+
+/// Milliseconds allowed for each `(year, day, part)`, parsing included.
+const BUDGETS: &[(u16, u8, u8, u64)] = &[
+    (2022, 1, 1, 5),
+    (2022, 1, 2, 5),
+    (2022, 2, 1, 5),
+    (2022, 2, 2, 5),
+    (2022, 3, 1, 5),
+    (2022, 3, 2, 5),
+    (2022, 4, 1, 5),
+    (2022, 4, 2, 5),
+    (2022, 5, 1, 5),
+    (2022, 5, 2, 5),
+    (2022, 6, 1, 5),
+    (2022, 6, 2, 10),
+    (2022, 7, 1, 5),
+    (2022, 7, 2, 5),
+    (2022, 8, 1, 5),
+    (2022, 8, 2, 5),
+    (2022, 9, 1, 5),
+    (2022, 9, 2, 5),
+];
+
+/// Each part runs this many times and the fastest run is compared against
+/// its budget, so one slow run on a busy machine doesn't fail the suite.
+const RUNS: usize = 5;
+
+fn budget(year: u16, day: u8, part: u8) -> Option<Duration> {
+    BUDGETS
+        .iter()
+        .find(|(y, d, p, _)| (*y, *d, *p) == (year, day, part))
+        .map(|(_, _, _, ms)| Duration::from_millis(*ms))
+}
+
+#[test]
+#[cfg_attr(debug_assertions, ignore = "timings need an optimized build")]
+fn parts_stay_within_budget() {
+    let manifest = inputs::manifest().unwrap();
+    let mut report = Vec::new();
+    for year in days::YEARS {
+        for info in year.days {
+            let expected = manifest
+                .iter()
+                .find(|e| e.year == year.year && e.day == info.day && e.is_real())
+                .unwrap_or_else(|| panic!("{} day {} has no real input", year.year, info.day));
+            let input = std::fs::read_to_string(expected.path()).unwrap();
+            for part in [1, 2] {
+                let label = format!("{} day {} part {}", year.year, info.day, part);
+                let budget = match budget(year.year, info.day, part) {
+                    Some(budget) => budget,
+                    None => {
+                        report.push(format!("{}: no budget set", label));
+                        continue;
+                    }
+                };
+                let solve = info.part(part).unwrap();
+                let fastest = (0..RUNS)
+                    .map(|_| {
+                        let start = Instant::now();
+                        solve(&input, &Cancel::new()).unwrap();
+                        start.elapsed()
+                    })
+                    .min()
+                    .unwrap();
+                if fastest > budget {
+                    report.push(format!(
+                        "{}: took {:?}, budget is {:?}",
+                        label, fastest, budget
+                    ));
+                }
+            }
+        }
+    }
+    assert!(report.is_empty(), "over budget:\n  {}", report.join("\n  "));
+}
+
+fn debug_prints(dir: &Path, found: &mut Vec<String>) {
+    for entry in std::fs::read_dir(dir).unwrap() {
+        let path = entry.unwrap().path();
+        if path.is_dir() {
+            debug_prints(&path, found);
+        } else if path.extension().is_some_and(|ext| ext == "rs") {
+            let source = std::fs::read_to_string(&path).unwrap();
+            for (n, line) in source.lines().enumerate() {
+                if line.contains("dbg!(") {
+                    found.push(format!("{}:{}: {}", path.display(), n + 1, line.trim()));
+                }
+            }
+        }
+    }
+}
+
+#[test]
+fn no_debug_prints_in_solvers() {
+    let mut found = Vec::new();
+    debug_prints(Path::new("src/days"), &mut found);
+    assert!(
+        found.is_empty(),
+        "dbg! left in a solver:\n  {}",
+        found.join("\n  ")
+    );
+}
@@ -0,0 +1,107 @@
+//! Exercises the C ABI from C: checks the committed header matches what
+//! cbindgen generates, then compiles `tests/ffi/smoke.c` against the cdylib
+//! and runs it on the shipped example inputs.
+
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+const EXAMPLES: &[(u8, u8, &str, &str)] = &[
+    (1, 1, "day1_example.txt", "24000"),
+    (1, 2, "day1_example.txt", "45000"),
+    (2, 1, "day2_example.txt", "15"),
+    (2, 2, "day2_example.txt", "12"),
+    (3, 1, "day3_example.txt", "157"),
+    (3, 2, "day3_example.txt", "70"),
+    (4, 1, "day4_example.txt", "2"),
+    (4, 2, "day4_example.txt", "4"),
+    (5, 1, "day5_example.txt", "CMZ"),
+    (5, 2, "day5_example.txt", "MCD"),
+    (6, 1, "day6_example.txt", "11"),
+    (6, 2, "day6_example.txt", "26"),
+    (7, 1, "day7_example.txt", "95437"),
+    (7, 2, "day7_example.txt", "24933642"),
+    (8, 1, "day8_example.txt", "21"),
+    (8, 2, "day8_example.txt", "8"),
+    (9, 1, "day9_example.txt", "13"),
+    (9, 2, "day9_example_2.txt", "36"),
+];
+
+fn manifest_dir() -> &'static Path {
+    Path::new(env!("CARGO_MANIFEST_DIR"))
+}
+
+#[test]
+fn header_is_up_to_date() {
+    let config = cbindgen::Config::from_file(manifest_dir().join("cbindgen.toml")).unwrap();
+    let mut generated = Vec::new();
+    cbindgen::Builder::new()
+        .with_crate(manifest_dir())
+        .with_config(config)
+        .generate()
+        .expect("cbindgen failed")
+        .write(&mut generated);
+    let generated = String::from_utf8(generated).unwrap();
+
+    let path = manifest_dir().join("include/advent.h");
+    if std::env::var_os("UPDATE_HEADER").is_some() {
+        std::fs::write(&path, &generated).unwrap();
+    }
+    let committed = std::fs::read_to_string(&path).unwrap_or_default();
+    assert!(
+        committed == generated,
+        "include/advent.h is stale, rerun with UPDATE_HEADER=1"
+    );
+}
+
+/// The directory cargo put the cdylib in, next to this test's `deps` dir.
+fn library_dir() -> PathBuf {
+    let name = format!(
+        "{}advent{}",
+        std::env::consts::DLL_PREFIX,
+        std::env::consts::DLL_SUFFIX
+    );
+    let deps = std::env::current_exe()
+        .unwrap()
+        .parent()
+        .unwrap()
+        .to_owned();
+    [deps.parent().unwrap().to_owned(), deps]
+        .into_iter()
+        .find(|dir| dir.join(&name).exists())
+        .unwrap_or_else(|| panic!("{} not found next to the test binary", name))
+}
+
+#[test]
+fn c_program_solves_examples() {
+    let lib_dir = library_dir();
+    let program = Path::new(env!("CARGO_TARGET_TMPDIR")).join("ffi_smoke");
+    let cc = std::env::var("CC").unwrap_or_else(|_| "cc".to_owned());
+
+    let status = Command::new(cc)
+        .arg(manifest_dir().join("tests/ffi/smoke.c"))
+        .arg("-I")
+        .arg(manifest_dir().join("include"))
+        .arg("-L")
+        .arg(&lib_dir)
+        .arg(format!("-Wl,-rpath,{}", lib_dir.display()))
+        .arg("-ladvent")
+        .arg("-o")
+        .arg(&program)
+        .status()
+        .expect("failed to run the C compiler");
+    assert!(status.success(), "compiling smoke.c failed");
+
+    let mut run = Command::new(&program);
+    for (day, part, input, answer) in EXAMPLES {
+        run.arg(day.to_string())
+            .arg(part.to_string())
+            .arg(manifest_dir().join("inputs/2022").join(input))
+            .arg(answer);
+    }
+    let output = run.output().unwrap();
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+}
@@ -0,0 +1,82 @@
+/* Calls advent_solve on each (day, part, input file, expected answer) group
+ * given on the command line and exits non-zero on the first mismatch. */
+#include <stdio.h>
+#include <stdlib.h>
+#include <string.h>
+
+#include "advent.h"
+
+static char *read_file(const char *path, size_t *len) {
+    FILE *file = fopen(path, "rb");
+    if (!file) {
+        perror(path);
+        exit(2);
+    }
+    fseek(file, 0, SEEK_END);
+    *len = (size_t)ftell(file);
+    fseek(file, 0, SEEK_SET);
+    char *data = malloc(*len);
+    if (fread(data, 1, *len, file) != *len) {
+        perror(path);
+        exit(2);
+    }
+    fclose(file);
+    return data;
+}
+
+int main(int argc, char **argv) {
+    char out[64];
+    size_t out_len;
+
+    for (int i = 1; i + 3 < argc; i += 4) {
+        unsigned day = (unsigned)atoi(argv[i]);
+        unsigned part = (unsigned)atoi(argv[i + 1]);
+        size_t len;
+        char *input = read_file(argv[i + 2], &len);
+
+        out_len = sizeof(out);
+        AdventStatus status = advent_solve(day, part, (const uint8_t *)input, len,
+                                           (uint8_t *)out, &out_len);
+        if (status != ADVENT_STATUS_OK || strcmp(out, argv[i + 3]) != 0) {
+            fprintf(stderr, "day %u part %u: status %d, got \"%s\", want \"%s\"\n",
+                    day, part, (int)status, status == ADVENT_STATUS_OK ? out : "",
+                    argv[i + 3]);
+            return 1;
+        }
+
+        out_len = 1;
+        status = advent_solve(day, part, (const uint8_t *)input, len, (uint8_t *)out, &out_len);
+        if (status != ADVENT_STATUS_BUFFER_TOO_SMALL || out_len != strlen(argv[i + 3]) + 1) {
+            fprintf(stderr, "day %u part %u: expected BUFFER_TOO_SMALL asking for %zu, got %d asking for %zu\n",
+                    day, part, strlen(argv[i + 3]) + 1, (int)status, out_len);
+            return 1;
+        }
+
+        /* The examples are all from the latest year, 2022. */
+        out_len = sizeof(out);
+        status = advent_solve_year(2022, day, part, (const uint8_t *)input, len, (uint8_t *)out,
+                                   &out_len);
+        if (status != ADVENT_STATUS_OK || strcmp(out, argv[i + 3]) != 0) {
+            fprintf(stderr, "2022 day %u part %u: status %d from advent_solve_year\n", day, part,
+                    (int)status);
+            return 1;
+        }
+        free(input);
+    }
+
+    out_len = sizeof(out);
+    if (advent_solve(42, 1, (const uint8_t *)"", 0, (uint8_t *)out, &out_len) !=
+        ADVENT_STATUS_UNKNOWN_SOLVER) {
+        fprintf(stderr, "day 42 should be unknown\n");
+        return 1;
+    }
+    out_len = sizeof(out);
+    if (advent_solve_year(1999, 1, 1, (const uint8_t *)"", 0, (uint8_t *)out, &out_len) !=
+        ADVENT_STATUS_UNKNOWN_SOLVER) {
+        fprintf(stderr, "year 1999 should be unknown\n");
+        return 1;
+    }
+
+    printf("ok\n");
+    return 0;
+}
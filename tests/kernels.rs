@@ -0,0 +1,128 @@
+//! Runs the WGSL kernels under `assets/shaders` on `advent::kernel`'s CPU
+//! interpreter. Needs the `kernel` feature.
+
+use std::path::Path;
+
+use advent::days::y2022::day1::{KernelLayout, PAINT_WORKGROUP_SIZE, SUM_WORKGROUP_SIZE};
+use advent::kernel::{Bindings, Kernel, Texture};
+use advent::shaders::{self, Shader};
+
+fn kernel(shader: &Shader) -> Kernel {
+    Kernel::parse(shader.source).unwrap()
+}
+
+#[test]
+fn day1_init_paints_the_input() {
+    let kernel = kernel(&shaders::DAY1);
+    let mut input: Vec<u32> = (1..=10).collect();
+    input[6] = 0x123456;
+    input[9] = 0xff00ff;
+    let layout = KernelLayout::new(input.len()).unwrap();
+    let mut bindings = Bindings::new()
+        .with_texture(0, Texture::new(8, 8, [9; 4]))
+        .with_u32s(1, &input)
+        .with_u32s(2, &[0; 3])
+        .with_u32s(3, &[0; 10])
+        .with_u32s(4, &layout.dims())
+        .with_u32s(5, &[0]);
+    kernel
+        .dispatch("init", layout.init_workgroups(), &mut bindings)
+        .unwrap();
+
+    // The 10 lines fill a 4x3 texture row by row; the rest is left alone.
+    let texture = bindings.texture(0).unwrap();
+    assert_eq!(texture.get(2, 1), Some([18, 52, 86, 255]));
+    assert_eq!(texture.get(1, 2), Some([254, 0, 254, 255]));
+    assert_eq!(texture.get(2, 2), Some([0, 0, 0, 255]));
+    assert_eq!(texture.get(4, 0), Some([9; 4]));
+    assert_eq!(texture.get(0, 3), Some([9; 4]));
+}
+
+#[test]
+fn day1_finds_the_largest_totals() {
+    let kernel = kernel(&shaders::DAY1);
+    assert_eq!(
+        kernel.workgroup_size("init"),
+        Some([PAINT_WORKGROUP_SIZE, PAINT_WORKGROUP_SIZE, 1])
+    );
+    assert_eq!(
+        kernel.workgroup_size("sum"),
+        Some([SUM_WORKGROUP_SIZE, 1, 1])
+    );
+
+    // Blank lines come from the mask, so the 0 after the first 9 is an elf's
+    // line like any other.
+    let input = [5, 0, 1, 2, 0, 9, 0, 9, 0, 4, 3, 0, 6];
+    let blank_lines = 0b1001_0001_0010;
+    let layout = KernelLayout::new(input.len()).unwrap();
+    // Only the first `lines` words count, whatever the buffers' sizes.
+    let mut bindings = Bindings::new()
+        .with_texture(0, Texture::new(1, 1, [0; 4]))
+        .with_u32s(1, &[&input[..], &[100]].concat())
+        .with_u32s(2, &[0; 3])
+        .with_u32s(3, &[7; 14])
+        .with_u32s(4, &layout.dims())
+        .with_u32s(5, &[blank_lines]);
+    kernel
+        .dispatch("sum", layout.sum_workgroups(), &mut bindings)
+        .unwrap();
+    assert_eq!(
+        bindings.u32s(3).unwrap(),
+        [5, 0, 3, 0, 0, 18, 0, 0, 0, 7, 0, 0, 6, 7]
+    );
+    kernel.dispatch("top3", [1, 1, 1], &mut bindings).unwrap();
+    assert_eq!(bindings.u32s(2).unwrap(), [18, 7, 6]);
+}
+
+#[test]
+fn gradient_reports_the_picked_color() {
+    let kernel = kernel(&shaders::GRADIENT);
+    let mut bindings = Bindings::new()
+        .with_texture(0, Texture::new(16, 16, [0; 4]))
+        .with_f32s(1, &[0.5; 4])
+        .with_f32s(2, &[300.0, 50.0]);
+    kernel.dispatch("init", [2, 2, 1], &mut bindings).unwrap();
+    assert_eq!(bindings.f32s(1).unwrap(), [1.0, 0.0, 0.0, 1.0]);
+    let texture = bindings.texture(0).unwrap();
+    assert_eq!(texture.get(0, 0), Some([0, 0, 0, 255]));
+    assert_eq!(texture.get(15, 3), Some([15, 6, 0, 255]));
+
+    kernel.dispatch("update", [1, 1, 1], &mut bindings).unwrap();
+    assert_eq!(
+        bindings.f32s(1).unwrap(),
+        [44.0 / 256.0, 50.0 / 129.0, 0.0, 1.0]
+    );
+}
+
+#[test]
+fn old_update_sums_runs() {
+    let root = Path::new(env!("CARGO_MANIFEST_DIR"));
+    let kernel = Kernel::load(&root.join("assets/shaders/old.wgsl")).unwrap();
+    let mut texture = Texture::new(8, 1, [0; 4]);
+    for x in 1..8 {
+        texture.set(x, 0, [0, 1, x as u8 + 2, 255]);
+    }
+    let mut bindings = Bindings::new().with_texture(0, texture.clone());
+    kernel.dispatch("init", [1, 1, 1], &mut bindings).unwrap();
+    assert_eq!(bindings.texture(0), Some(&texture));
+
+    kernel.dispatch("update", [1, 1, 1], &mut bindings).unwrap();
+    let row: Vec<_> = (0..8)
+        .map(|x| bindings.texture(0).unwrap().get(x, 0).unwrap())
+        .collect();
+    // The start of each run adds its neighbor; the rest move one left. The
+    // shader takes a texel's low byte from alpha, so 0x01ff + 0x01ff.
+    assert_eq!(
+        row,
+        [
+            [0, 0, 0, 0],
+            [0, 3, 254, 255],
+            [0, 1, 5, 255],
+            [0, 1, 6, 255],
+            [0, 1, 7, 255],
+            [0, 1, 8, 255],
+            [0, 1, 9, 255],
+            [0, 0, 0, 0],
+        ]
+    );
+}
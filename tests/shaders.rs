@@ -0,0 +1,222 @@
+//! Parses and validates every WGSL file under `assets/shaders` on the CPU,
+//! and checks them against the bind group layouts in `advent::shaders`, so a
+//! broken shader shows up here rather than on a GPU at runtime.
+
+use std::path::{Path, PathBuf};
+
+use naga::valid::{Capabilities, ValidationFlags, Validator};
+use naga::{AddressSpace, ImageClass, ImageDimension, Module, StorageAccess, TypeInner};
+use regex::Regex;
+
+use advent::shaders::{BindingKind, Shader, SHADERS};
+
+fn root() -> &'static Path {
+    Path::new(env!("CARGO_MANIFEST_DIR"))
+}
+
+fn wgsl_files() -> Vec<PathBuf> {
+    let mut files: Vec<_> = std::fs::read_dir(root().join("assets/shaders"))
+        .unwrap()
+        .map(|entry| entry.unwrap().path())
+        .filter(|path| path.extension().is_some_and(|ext| ext == "wgsl"))
+        .collect();
+    files.sort();
+    files
+}
+
+/// Parses and validates the shader at `path`, describing what went wrong.
+fn load(path: &Path) -> Result<Module, String> {
+    let name = path.display().to_string();
+    let source = std::fs::read_to_string(path).map_err(|err| format!("{}: {}", name, err))?;
+    let module = naga::front::wgsl::parse_str(&source)
+        .map_err(|err| err.emit_to_string_with_path(&source, &name))?;
+    Validator::new(ValidationFlags::all(), Capabilities::empty())
+        .validate(&module)
+        .map_err(|err| err.emit_to_string_with_path(&source, &name))?;
+    Ok(module)
+}
+
+/// How the shader declares each binding of group 0, in binding order.
+fn bindings(module: &Module) -> Result<Vec<(u32, BindingKind)>, String> {
+    let mut found = Vec::new();
+    for (_, global) in module.global_variables.iter() {
+        let binding = match &global.binding {
+            Some(binding) if binding.group == 0 => binding.binding,
+            Some(binding) => return Err(format!("binding in group {}", binding.group)),
+            None => continue,
+        };
+        let inner = &module.types[global.ty].inner;
+        let kind = match (global.space, inner) {
+            (AddressSpace::Storage { access }, _) => BindingKind::Storage {
+                read_only: !access.contains(StorageAccess::STORE),
+            },
+            (AddressSpace::Uniform, _) => BindingKind::Uniform {
+                size: inner.size(&module.constants) as u64,
+            },
+            (
+                AddressSpace::Handle,
+                TypeInner::Image {
+                    dim: ImageDimension::D2,
+                    arrayed: false,
+                    class:
+                        ImageClass::Storage {
+                            format: naga::StorageFormat::Rgba8Unorm,
+                            access,
+                        },
+                },
+            ) if *access == StorageAccess::LOAD | StorageAccess::STORE => {
+                BindingKind::StorageTexture
+            }
+            _ => {
+                return Err(format!(
+                    "binding {} has unsupported type {:?}",
+                    binding, inner
+                ))
+            }
+        };
+        found.push((binding, kind));
+    }
+    found.sort_by_key(|(binding, _)| *binding);
+    Ok(found)
+}
+
+/// Differences between the layout a pipeline creates and what the shader
+/// declares.
+fn layout_mismatches(shader: &Shader, module: &Module) -> Vec<String> {
+    let declared = match bindings(module) {
+        Ok(declared) => declared,
+        Err(err) => return vec![err],
+    };
+    let mut mismatches = Vec::new();
+    for entry in shader.bindings {
+        let kind = match declared
+            .iter()
+            .find(|(binding, _)| *binding == entry.binding)
+        {
+            Some((_, kind)) => *kind,
+            None => {
+                mismatches.push(format!("binding {} is unused", entry.binding));
+                continue;
+            }
+        };
+        let compatible = match (entry.kind, kind) {
+            // A uniform binding may be larger than the shader reads.
+            (BindingKind::Uniform { size: bound }, BindingKind::Uniform { size: read }) => {
+                bound >= read
+            }
+            (expected, declared) => expected == declared,
+        };
+        if !compatible {
+            mismatches.push(format!(
+                "binding {}: layout has {:?}, shader has {:?}",
+                entry.binding, entry.kind, kind
+            ));
+        }
+    }
+    for (binding, kind) in &declared {
+        if !shader
+            .bindings
+            .iter()
+            .any(|entry| entry.binding == *binding)
+        {
+            mismatches.push(format!(
+                "binding {} ({:?}) has no layout entry",
+                binding, kind
+            ));
+        }
+    }
+    for name in shader.entry_points {
+        match module.entry_points.iter().find(|entry| entry.name == *name) {
+            Some(entry) if entry.stage == naga::ShaderStage::Compute => {}
+            Some(entry) => mismatches.push(format!("{} is a {:?} entry point", name, entry.stage)),
+            None => mismatches.push(format!("no entry point {}", name)),
+        }
+    }
+    mismatches
+}
+
+#[test]
+fn every_shader_validates() {
+    let files = wgsl_files();
+    assert!(!files.is_empty(), "no shaders found");
+    let failures: Vec<_> = files.iter().filter_map(|path| load(path).err()).collect();
+    assert!(failures.is_empty(), "{}", failures.join("\n"));
+}
+
+#[test]
+fn layouts_match_shaders() {
+    let mut failures = Vec::new();
+    for shader in SHADERS {
+        let path = root().join("assets").join(shader.path);
+        match load(&path) {
+            Ok(module) => failures.extend(
+                layout_mismatches(shader, &module)
+                    .into_iter()
+                    .map(|mismatch| format!("{}: {}", shader.path, mismatch)),
+            ),
+            Err(err) => failures.push(err),
+        }
+    }
+    assert!(failures.is_empty(), "{}", failures.join("\n"));
+}
+
+/// Shader paths written out in the source rather than taken from
+/// `advent::shaders`.
+fn referenced_paths(dir: &Path, re: &Regex, found: &mut Vec<(PathBuf, String)>) {
+    for entry in std::fs::read_dir(dir).unwrap() {
+        let path = entry.unwrap().path();
+        if path.is_dir() {
+            referenced_paths(&path, re, found);
+        } else if path.extension().is_some_and(|ext| ext == "rs") {
+            let source = std::fs::read_to_string(&path).unwrap();
+            for captures in re.captures_iter(&source) {
+                found.push((path.clone(), captures[1].to_owned()));
+            }
+        }
+    }
+}
+
+#[test]
+fn referenced_shaders_exist() {
+    let re = Regex::new(r#""([^"\s]+\.wgsl)""#).unwrap();
+    let mut found = Vec::new();
+    referenced_paths(&root().join("src"), &re, &mut found);
+    referenced_paths(&root().join("examples"), &re, &mut found);
+    assert!(!found.is_empty(), "no shader references found");
+    let missing: Vec<_> = found
+        .iter()
+        .filter(|(_, shader)| !root().join("assets").join(shader).is_file())
+        .map(|(file, shader)| format!("{} loads missing {}", file.display(), shader))
+        .collect();
+    assert!(missing.is_empty(), "{}", missing.join("\n"));
+}
+
+#[test]
+fn mismatched_layouts_are_reported() {
+    let source = "@group(0) @binding(0) var<storage> values: array<u32>;
+         @group(0) @binding(1) var<uniform> size: vec2<u32>;
+         @compute @workgroup_size(1) fn main() { let x = values[0] + size.x; }";
+    let module = naga::front::wgsl::parse_str(source).unwrap();
+    let shader = Shader {
+        path: "inline",
+        source,
+        entry_points: &["main", "init"],
+        bindings: &[
+            advent::shaders::Binding {
+                binding: 0,
+                kind: BindingKind::Storage { read_only: false },
+            },
+            advent::shaders::Binding {
+                binding: 1,
+                kind: BindingKind::Uniform { size: 16 },
+            },
+        ],
+    };
+    assert_eq!(
+        layout_mismatches(&shader, &module),
+        [
+            "binding 0: layout has Storage { read_only: false }, shader has Storage { read_only: true }",
+            "no entry point init",
+        ]
+    );
+}
@@ -0,0 +1,209 @@
+//! Compares rendered animation frames for the example inputs against the
+//! files under `tests/snapshots`.
+//!
+//! After an intended change to how a day draws, rerun with
+//! `UPDATE_SNAPSHOTS=1 cargo test --test snapshots` to rewrite the files, and
+//! review them in the diff like any other change. Text snapshots fail with a
+//! line diff; image snapshots write what was drawn next to the expected file
+//! as `*.actual.png`.
+
+use std::path::{Path, PathBuf};
+
+use advent::days;
+use advent::raster::{Canvas, Rgb};
+use advent::visualize::Frame;
+
+/// Which frames of each example to keep, by index; negative counts from the
+/// end.
+const TEXT_SNAPSHOTS: &[(u8, &str, &[isize])] = &[
+    (5, "day5_example.txt", &[0, 1, 2, 3, 4]),
+    (9, "day9_example.txt", &[0, 1, 4, -1]),
+    (9, "day9_example_2.txt", &[2, -1]),
+];
+
+const IMAGE_SNAPSHOTS: &[(u8, &str, isize)] =
+    &[(5, "day5_example.txt", -1), (9, "day9_example.txt", -1)];
+
+fn updating() -> bool {
+    std::env::var_os("UPDATE_SNAPSHOTS").is_some_and(|value| value != "0")
+}
+
+fn snapshot_path(name: &str) -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join("tests/snapshots")
+        .join(name)
+}
+
+fn frames(day: u8, example: &str) -> Vec<Frame> {
+    let path = Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join("inputs/2022")
+        .join(example);
+    let input = std::fs::read_to_string(&path).unwrap();
+    let visualize = days::info(2022, day).unwrap().visualize.unwrap();
+    visualize(&input).unwrap()
+}
+
+fn pick(frames: &[Frame], index: isize) -> (usize, &Frame) {
+    let index = if index < 0 {
+        frames.len() - index.unsigned_abs()
+    } else {
+        index as usize
+    };
+    (index, &frames[index])
+}
+
+/// What changed from `expected` to `actual`, one line each, with two lines of
+/// context around every change.
+fn diff(expected: &str, actual: &str) -> String {
+    let (old, new): (Vec<_>, Vec<_>) = (expected.lines().collect(), actual.lines().collect());
+
+    // Longest common subsequence lengths of every pair of suffixes.
+    let mut lcs = vec![vec![0; new.len() + 1]; old.len() + 1];
+    for i in (0..old.len()).rev() {
+        for j in (0..new.len()).rev() {
+            lcs[i][j] = if old[i] == new[j] {
+                lcs[i + 1][j + 1] + 1
+            } else {
+                lcs[i + 1][j].max(lcs[i][j + 1])
+            };
+        }
+    }
+
+    let mut edits = Vec::new();
+    let (mut i, mut j) = (0, 0);
+    while i < old.len() || j < new.len() {
+        if i < old.len() && j < new.len() && old[i] == new[j] {
+            edits.push((' ', i + 1, old[i]));
+            i += 1;
+            j += 1;
+        } else if i < old.len() && (j == new.len() || lcs[i + 1][j] >= lcs[i][j + 1]) {
+            edits.push(('-', i + 1, old[i]));
+            i += 1;
+        } else {
+            edits.push(('+', j + 1, new[j]));
+            j += 1;
+        }
+    }
+
+    let near_change = |at: usize| {
+        edits[at.saturating_sub(2)..(at + 3).min(edits.len())]
+            .iter()
+            .any(|(tag, _, _)| *tag != ' ')
+    };
+    let mut out = String::new();
+    let mut skipped = false;
+    for (at, (tag, line, text)) in edits.iter().enumerate() {
+        if near_change(at) {
+            if skipped {
+                out.push_str("    ...\n");
+                skipped = false;
+            }
+            out.push_str(&format!("{} {:>3} |{}\n", tag, line, text));
+        } else {
+            skipped = true;
+        }
+    }
+    out
+}
+
+/// Checks `actual` against the snapshot `name`, or rewrites it when updating.
+/// Returns a description of the mismatch, if any.
+fn check_text(name: &str, actual: &str) -> Option<String> {
+    let path = snapshot_path(name);
+    if updating() {
+        std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+        std::fs::write(&path, actual).unwrap();
+        return None;
+    }
+    match std::fs::read_to_string(&path) {
+        Ok(expected) if expected == actual => None,
+        Ok(expected) => Some(format!(
+            "{} differs (- snapshot, + rendered):\n{}",
+            path.display(),
+            diff(&expected, actual)
+        )),
+        Err(_) => Some(format!("{} is missing", path.display())),
+    }
+}
+
+fn check_image(name: &str, canvas: &Canvas) -> Option<String> {
+    let path = snapshot_path(name);
+    if updating() {
+        std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+        canvas.save_png(&path).unwrap();
+        return None;
+    }
+    let actual_path = path.with_extension("actual.png");
+    let expected = match std::fs::read(&path) {
+        Ok(bytes) => bytes,
+        Err(_) => return Some(format!("{} is missing", path.display())),
+    };
+    let mut reader = png::Decoder::new(expected.as_slice()).read_info().unwrap();
+    let mut pixels = vec![0; reader.output_buffer_size()];
+    let info = reader.next_frame(&mut pixels).unwrap();
+    let size = (info.width as usize, info.height as usize);
+    if size == (canvas.width(), canvas.height()) && pixels == canvas.to_rgb_bytes() {
+        let _ = std::fs::remove_file(&actual_path);
+        return None;
+    }
+    canvas.save_png(&actual_path).unwrap();
+    Some(format!(
+        "{} differs; the rendered image is in {}",
+        path.display(),
+        actual_path.display()
+    ))
+}
+
+#[test]
+fn text_frames_match_snapshots() {
+    let mut failures = Vec::new();
+    for &(day, example, picks) in TEXT_SNAPSHOTS {
+        let frames = frames(day, example);
+        let mut text = String::new();
+        for &index in picks {
+            let (index, frame) = pick(&frames, index);
+            text.push_str(&format!(
+                "--- frame {} of {}: {}\n",
+                index + 1,
+                frames.len(),
+                frame.caption
+            ));
+            text.push_str(&frame.to_string());
+        }
+        let name = example.replace(".txt", ".snap");
+        failures.extend(check_text(&name, &text));
+    }
+    assert!(
+        failures.is_empty(),
+        "{}\nrun with UPDATE_SNAPSHOTS=1 to accept the new rendering",
+        failures.join("\n")
+    );
+}
+
+#[test]
+fn image_frames_match_snapshots() {
+    let mut failures = Vec::new();
+    for &(day, example, index) in IMAGE_SNAPSHOTS {
+        let frames = frames(day, example);
+        let (index, frame) = pick(&frames, index);
+        let canvas = frame.to_canvas(1, Rgb::WHITE, Rgb::BLACK);
+        let name = example.replace(".txt", &format!("_frame{}.png", index + 1));
+        failures.extend(check_image(&name, &canvas));
+    }
+    assert!(
+        failures.is_empty(),
+        "{}\nrun with UPDATE_SNAPSHOTS=1 to accept the new rendering",
+        failures.join("\n")
+    );
+}
+
+#[test]
+fn diffs_show_changed_lines_in_context() {
+    let expected = "a\nb\nc\nd\ne\nf\ng\n";
+    let actual = "a\nb\nc\nd\nE\nf\ng\n";
+    assert_eq!(
+        diff(expected, actual),
+        "    ...\n    3 |c\n    4 |d\n-   5 |e\n+   5 |E\n    6 |f\n    7 |g\n"
+    );
+    assert_eq!(diff("x\n", "x\ny\n"), "    1 |x\n+   2 |y\n");
+}